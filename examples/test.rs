@@ -40,14 +40,16 @@ fn message_create(ctx: Context, msg: Message) {
 }
 
 fn ping(ctx: &Context, msg: &Message) -> harmony::Result {
-    let reply = ctx.create_message(msg.channel_id, |m| m.content("Pong!"))?;
-    let duration = reply.timestamp - msg.timestamp;
-    ctx.edit_message(reply.channel_id, reply.id, |m| {
-        m.content(format!(
-            "Pong! That took {} ms.",
-            duration.num_milliseconds()
-        ))
-    })?;
+    let content = match ctx.latency() {
+        Some(latency) => format!(
+            "Pong! Gateway latency is {} ms ({} ms on average).",
+            latency.last.as_millis(),
+            latency.average.as_millis()
+        ),
+        // No heartbeat has been acknowledged yet
+        None => "Pong!".to_string(),
+    };
+    ctx.create_message(msg.channel_id, |m| m.content(content))?;
     Ok(())
 }
 
@@ -62,7 +64,7 @@ fn newrole(ctx: &Context, msg: &Message, args: &[&str]) -> harmony::Result {
         ctx.create_message(msg.channel_id, |m| m.content("Not in a guild"))?;
         return Ok(());
     };
-    ctx.create_guild_role(guild_id, |r| r.name(&args[0]))?;
+    ctx.create_guild_role(guild_id, |r| r.name(args[0]))?;
     Ok(())
 }
 
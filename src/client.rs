@@ -1,67 +1,105 @@
+#[cfg(feature = "async")]
+mod async_builder;
+#[cfg(feature = "async")]
+mod async_client;
+#[cfg(feature = "async")]
+mod async_context;
+#[cfg(feature = "async")]
+mod async_event_handler;
+#[cfg(feature = "async")]
+mod async_gateway_handler;
 mod builder;
 mod context;
+mod dispatch_policy;
+mod event_dispatcher;
 mod event_handler;
 mod gateway_handler;
+mod identify_queue;
+mod latency;
+mod session;
+mod shard_manager;
+mod shutdown_handle;
 
+use std::panic;
 use std::sync::Arc;
-use std::thread;
-use std::time::Duration;
 
-use crate::gateway::{Gateway, Intents, Ready};
-use crate::model::Message;
+use crate::gateway::Shard;
+use crate::http::Http;
 use crate::Result;
+#[cfg(feature = "async")]
+pub use async_builder::AsyncClientBuilder;
+#[cfg(feature = "async")]
+pub use async_client::AsyncClient;
+#[cfg(feature = "async")]
+pub use async_context::AsyncContext;
 pub use builder::ClientBuilder;
 pub use context::Context;
-use event_handler::EventHandler;
-use gateway_handler::GatewayHandler;
+pub use dispatch_policy::{DispatchOrdering, DispatchPolicy};
+use event_dispatcher::{EventDispatcher, ShardEvent};
+pub use event_handler::EventHandler;
+pub use latency::Latency;
 use parking_lot::Mutex;
+pub use shard_manager::{ShardInfo, ShardManager, ShardStatus};
+pub use shutdown_handle::ShutdownHandle;
 
-pub(crate) type Callback<'a, T> = Arc<Mutex<dyn FnMut(Context, T) + 'a>>;
+pub(crate) type Callback<'a, T> = Arc<Mutex<dyn FnMut(Context, T) + Send + 'a>>;
+#[cfg(feature = "async")]
+pub(crate) type AsyncCallback<T> =
+    Arc<dyn Fn(AsyncContext, T) -> futures_util::future::BoxFuture<'static, ()> + Send + Sync>;
 
 pub struct Client<'a> {
-    token: String,
-    intents: Intents,
-    on_ready: Callback<'a, Ready>,
-    on_message_create: Callback<'a, Message>,
+    http: Http,
+    shard: Option<Shard>,
+    shard_count: Option<u64>,
+    shard_manager: ShardManager,
+    event_handler: Box<dyn EventHandler + 'a>,
+    dispatch_policy: DispatchPolicy,
 }
 
 impl<'a> Client<'a> {
-    pub fn run(self) -> Result<()> {
-        loop {
-            match self.connect() {
-                Ok((gateway_handler, event_handler)) => {
-                    crossbeam_utils::thread::scope(move |s| {
-                        s.spawn(|_| {
-                            if let Err(err) = gateway_handler.run() {
-                                eprintln!("GatewayHandler::run err: {:?}", err);
-                            }
-                        });
-                        if let Err(err) = event_handler.run() {
-                            eprintln!("EventHandler::run err: {:?}", err);
-                        }
-                    })
-                    .ok();
-                }
-                Err(err) => {
-                    eprintln!("Client::connect err: {:?}", err);
-                    thread::sleep(Duration::from_secs(5));
-                }
-            }
-        }
+    pub fn shard_manager(&self) -> &ShardManager {
+        &self.shard_manager
     }
 
-    fn connect(&self) -> Result<(GatewayHandler, EventHandler<'a>)> {
-        let gateway = Arc::new(Mutex::new(Gateway::connect(self.intents)?));
-        let token = Arc::<str>::from(self.token.clone());
-        let (event_sender, event_receiver) = crossbeam_channel::unbounded();
-        let gateway_handler = GatewayHandler::new(token.clone(), event_sender, gateway.clone());
-        let event_handler = EventHandler::new(
-            token,
+    pub fn run(mut self) -> Result<()> {
+        let gateway_bot = self.http.get_gateway_bot()?;
+        let recommended_shards = gateway_bot.shards;
+        self.shard_manager.set_gateway_bot(gateway_bot);
+        let shards = match self.shard {
+            Some(shard) => vec![shard],
+            None => {
+                let shard_count = self.shard_count.unwrap_or(recommended_shards).max(1);
+                (0..shard_count)
+                    .map(|shard_id| Shard::new(shard_id, shard_count))
+                    .collect()
+            }
+        };
+        let (event_sender, event_receiver) =
+            crossbeam_channel::bounded(self.dispatch_policy.queue_size);
+        let event_dispatcher = EventDispatcher::new(
+            self.http.clone(),
             event_receiver,
-            gateway,
-            self.on_ready.clone(),
-            self.on_message_create.clone(),
+            self.shard_manager.clone(),
+            self.event_handler,
+            self.dispatch_policy,
         );
-        Ok((gateway_handler, event_handler))
+        let shard_manager = &self.shard_manager;
+        crossbeam_utils::thread::scope(move |s| {
+            let shard_runners = shards
+                .into_iter()
+                .map(|shard| {
+                    let event_sender = event_sender.clone();
+                    s.spawn(move |_| shard_manager.run_shard(shard, event_sender))
+                })
+                .collect::<Vec<_>>();
+            drop(event_sender);
+            event_dispatcher.run()?;
+            shard_runners.into_iter().try_for_each(|shard_runner| {
+                shard_runner
+                    .join()
+                    .unwrap_or_else(|err| panic::resume_unwind(err))
+            })
+        })
+        .unwrap_or_else(|err| panic::resume_unwind(err))
     }
 }
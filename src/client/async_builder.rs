@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use super::event_handler::AsyncCallbacks;
+use super::AsyncClient;
+use crate::gateway::{Intents, Shard};
+use crate::http::AsyncHttp;
+use crate::Config;
+
+#[derive(Default)]
+pub struct AsyncClientBuilder {
+    token: Option<String>,
+    config: Config,
+    intents: Intents,
+    shard: Shard,
+    // The `on_*` methods are generated in `event_handler`.
+    pub(super) callbacks: AsyncCallbacks,
+}
+
+impl AsyncClientBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn build(self) -> AsyncClient {
+        let token = Arc::<str>::from(self.token.unwrap());
+        AsyncClient {
+            http: AsyncHttp::with_config(token, self.config.clone()),
+            config: self.config,
+            intents: self.intents,
+            shard: self.shard,
+            callbacks: self.callbacks,
+        }
+    }
+
+    pub fn with_bot_token(mut self, token: &str) -> Self {
+        self.token = Some(format!("Bot {}", token));
+        self
+    }
+
+    pub fn with_bearer_token(mut self, token: &str) -> Self {
+        self.token = Some(format!("Bearer {}", token));
+        self
+    }
+
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn intents(mut self, intents: Intents) -> Self {
+        self.intents = intents;
+        self
+    }
+
+    pub fn shard(mut self, shard_id: u64, shard_count: u64) -> Self {
+        self.shard = Shard::new(shard_id, shard_count);
+        self
+    }
+}
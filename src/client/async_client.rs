@@ -0,0 +1,54 @@
+use tokio::sync::mpsc;
+use tracing::error;
+
+use super::async_event_handler::AsyncEventHandler;
+use super::async_gateway_handler::AsyncGatewayHandler;
+use super::event_handler::AsyncCallbacks;
+use super::identify_queue::IdentifyQueue;
+use crate::gateway::{Intents, Shard};
+use crate::http::AsyncHttp;
+use crate::{Config, Result};
+
+pub struct AsyncClient {
+    pub(crate) config: Config,
+    pub(crate) http: AsyncHttp,
+    pub(crate) intents: Intents,
+    pub(crate) shard: Shard,
+    pub(crate) callbacks: AsyncCallbacks,
+}
+
+impl AsyncClient {
+    pub async fn run(mut self) -> Result<()> {
+        let gateway_bot = self.http.get_gateway_bot().await?;
+        self.config.gateway_url.get_or_insert(gateway_bot.url);
+        let identify_queue = IdentifyQueue::new();
+        identify_queue.set_session_start_limit(&gateway_bot.session_start_limit);
+        let (event_sender, event_receiver) = mpsc::unbounded_channel();
+        let (command_sender, command_receiver) = mpsc::unbounded_channel();
+        let gateway_handler = AsyncGatewayHandler::new(
+            self.http.clone(),
+            self.config,
+            self.intents,
+            self.shard,
+            identify_queue,
+            event_sender,
+            command_receiver,
+        );
+        let event_handler = AsyncEventHandler::new(
+            self.http,
+            event_receiver,
+            command_sender,
+            self.shard,
+            gateway_handler.latency(),
+            self.callbacks,
+        );
+        let event_handler = tokio::spawn(async move {
+            if let Err(err) = event_handler.run().await {
+                error!(error = ?err, "event handler failed");
+            }
+        });
+        let result = gateway_handler.run().await;
+        event_handler.abort();
+        result
+    }
+}
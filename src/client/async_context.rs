@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use serde_json::Value;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::Latency;
+use crate::gateway::{payload, Shard, Status};
+use crate::http::AsyncHttp;
+use crate::model::Activity;
+use crate::Result;
+
+#[derive(Debug, Clone)]
+pub struct AsyncContext {
+    http: AsyncHttp,
+    commands: UnboundedSender<Value>,
+    shard: Shard,
+    latency: Arc<Mutex<Option<Latency>>>,
+}
+
+impl AsyncContext {
+    pub(crate) fn new(
+        http: AsyncHttp,
+        commands: UnboundedSender<Value>,
+        shard: Shard,
+        latency: Arc<Mutex<Option<Latency>>>,
+    ) -> Self {
+        Self {
+            http,
+            commands,
+            shard,
+            latency,
+        }
+    }
+
+    pub fn shard(&self) -> Shard {
+        self.shard
+    }
+
+    // Heartbeat round-trip time of the shard, once it has been measured.
+    pub fn latency(&self) -> Option<Latency> {
+        *self.latency.lock()
+    }
+
+    pub fn presence_update(&self, status: Status, activity: Option<Activity>) -> Result<()> {
+        self.commands
+            .send(payload::presence_update(status, activity))?;
+        Ok(())
+    }
+}
+
+impl AsRef<AsyncHttp> for AsyncContext {
+    fn as_ref(&self) -> &AsyncHttp {
+        &self.http
+    }
+}
+
+impl std::ops::Deref for AsyncContext {
+    type Target = AsyncHttp;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_ref()
+    }
+}
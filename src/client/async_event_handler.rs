@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use serde_json::Value;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tracing::{debug, debug_span, Instrument};
+
+use super::event_handler::AsyncCallbacks;
+use super::{AsyncContext, Latency};
+use crate::gateway::{DispatchEvent, Shard};
+use crate::http::AsyncHttp;
+use crate::{Error, Result};
+
+pub struct AsyncEventHandler {
+    http: AsyncHttp,
+    event_receiver: UnboundedReceiver<DispatchEvent>,
+    commands: UnboundedSender<Value>,
+    shard: Shard,
+    latency: Arc<Mutex<Option<Latency>>>,
+    callbacks: AsyncCallbacks,
+}
+
+impl AsyncEventHandler {
+    pub fn new(
+        http: AsyncHttp,
+        event_receiver: UnboundedReceiver<DispatchEvent>,
+        commands: UnboundedSender<Value>,
+        shard: Shard,
+        latency: Arc<Mutex<Option<Latency>>>,
+        callbacks: AsyncCallbacks,
+    ) -> Self {
+        Self {
+            http,
+            event_receiver,
+            commands,
+            shard,
+            latency,
+            callbacks,
+        }
+    }
+
+    pub async fn run(mut self) -> Result {
+        while let Some(event) = self.event_receiver.recv().await {
+            let shard = match event.kind.guild_id() {
+                Some(guild_id) => Shard::for_guild(guild_id, self.shard.count),
+                None => self.shard,
+            };
+            let context = AsyncContext::new(
+                self.http.clone(),
+                self.commands.clone(),
+                shard,
+                self.latency.clone(),
+            );
+            let future = match self.callbacks.dispatch(context, event.kind) {
+                Some(future) => future,
+                None => {
+                    debug!(seq = event.sequence_number, "no callback for event");
+                    continue;
+                }
+            };
+            tokio::spawn(future.instrument(debug_span!(
+                "dispatch",
+                shard = shard.id,
+                seq = event.sequence_number
+            )));
+        }
+        Err(Error::Recv)
+    }
+}
@@ -0,0 +1,254 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use rand::Rng;
+use serde_json::Value;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::{oneshot, Mutex as AsyncMutex};
+use tracing::{debug, error, field, info, info_span, trace, warn, Instrument, Span};
+
+use super::identify_queue::IdentifyQueue;
+use super::latency::Latency;
+use super::session::Session;
+use crate::gateway::{
+    payload, AsyncGateway, AsyncGatewayReader, AsyncGatewayWriter, DispatchEvent, Event, Intents,
+    Shard,
+};
+use crate::http::AsyncHttp;
+use crate::{Config, Error, Result};
+
+#[derive(Debug)]
+pub struct AsyncGatewayHandler {
+    http: AsyncHttp,
+    config: Config,
+    intents: Intents,
+    shard: Shard,
+    identify_queue: IdentifyQueue,
+    event_sender: UnboundedSender<DispatchEvent>,
+    commands: Arc<AsyncMutex<UnboundedReceiver<Value>>>,
+    session: Arc<Mutex<Session>>,
+    latency: Arc<Mutex<Option<Latency>>>,
+    span: Span,
+}
+
+impl AsyncGatewayHandler {
+    pub fn new(
+        http: AsyncHttp,
+        config: Config,
+        intents: Intents,
+        shard: Shard,
+        identify_queue: IdentifyQueue,
+        event_sender: UnboundedSender<DispatchEvent>,
+        commands: UnboundedReceiver<Value>,
+    ) -> Self {
+        Self {
+            http,
+            config,
+            intents,
+            shard,
+            identify_queue,
+            event_sender,
+            commands: Arc::new(AsyncMutex::new(commands)),
+            session: Default::default(),
+            latency: Default::default(),
+            span: info_span!("gateway", shard = shard.id, seq = field::Empty),
+        }
+    }
+
+    pub fn latency(&self) -> Arc<Mutex<Option<Latency>>> {
+        self.latency.clone()
+    }
+
+    pub async fn run(self) -> Result {
+        let mut resume = false;
+        loop {
+            resume = match self.connect(resume).instrument(self.span.clone()).await {
+                Ok(resume) => resume,
+                Err(Error::GatewayClosed { code, reason }) if code.can_reconnect() => {
+                    warn!(parent: &self.span, ?code, reason, "gateway closed, reconnecting");
+                    code.can_resume()
+                }
+                Err(Error::GatewayClosed { code, reason }) => {
+                    error!(parent: &self.span, ?code, reason, "gateway closed, stopping");
+                    return Err(Error::GatewayClosed { code, reason });
+                }
+                Err(err) => {
+                    error!(parent: &self.span, error = ?err, "gateway connection failed");
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    true
+                }
+            };
+        }
+    }
+
+    // Runs one connection until it has to be replaced, and returns whether the
+    // next one should resume the session.
+    async fn connect(&self, resume: bool) -> Result<bool> {
+        let (session_id, sequence_number, config) = {
+            let session = self.session.lock();
+            let mut config = self.config.clone();
+            if let Some(url) = session.resume_url().filter(|_| resume) {
+                config.gateway_url = Some(url.to_owned());
+            }
+            (session.session_id.clone(), session.sequence_number, config)
+        };
+        let gateway =
+            AsyncGateway::connect_with_shard(config, &self.http, self.intents, self.shard).await?;
+        let (mut writer, reader) = gateway.split();
+        if resume && session_id.is_some() {
+            info!("resuming session");
+            writer
+                .resume(self.http.token(), &session_id, sequence_number)
+                .await?;
+        } else {
+            while let Some(delay) = self.identify_queue.reserve(self.shard) {
+                tokio::time::sleep(delay).await;
+            }
+            info!("identifying");
+            writer
+                .identify(self.http.token(), self.intents, self.shard)
+                .await?;
+        }
+        let (heartbeat_sender, heartbeat_receiver) = mpsc::unbounded_channel();
+        let (interval_sender, interval_receiver) = oneshot::channel();
+        let pending_heartbeat = Arc::new(Mutex::new(None));
+        let mut reader = tokio::spawn(
+            read(
+                reader,
+                self.event_sender.clone(),
+                self.session.clone(),
+                heartbeat_sender.clone(),
+                interval_sender,
+                pending_heartbeat.clone(),
+                self.latency.clone(),
+            )
+            .in_current_span(),
+        );
+        let mut heartbeater = tokio::spawn(
+            heartbeat(
+                interval_receiver,
+                heartbeat_sender,
+                self.session.clone(),
+                pending_heartbeat,
+            )
+            .in_current_span(),
+        );
+        let mut writer = tokio::spawn(
+            write(writer, heartbeat_receiver, self.commands.clone()).in_current_span(),
+        );
+        let result = tokio::select! {
+            biased;
+            result = &mut reader => result,
+            result = &mut heartbeater => result,
+            result = &mut writer => result,
+        };
+        reader.abort();
+        heartbeater.abort();
+        writer.abort();
+        match result {
+            Ok(result) => result,
+            Err(err) => std::panic::resume_unwind(err.into_panic()),
+        }
+    }
+}
+
+async fn read(
+    mut reader: AsyncGatewayReader,
+    event_sender: UnboundedSender<DispatchEvent>,
+    session: Arc<Mutex<Session>>,
+    heartbeat_sender: UnboundedSender<Value>,
+    interval_sender: oneshot::Sender<Duration>,
+    pending_heartbeat: Arc<Mutex<Option<Instant>>>,
+    latency: Arc<Mutex<Option<Latency>>>,
+) -> Result<bool> {
+    let mut interval_sender = Some(interval_sender);
+    while let Some(event) = reader.next_event().await? {
+        match event {
+            Event::Dispatch(dispatch_event) => {
+                session.lock().update(&dispatch_event);
+                Span::current().record("seq", dispatch_event.sequence_number);
+                event_sender.send(dispatch_event)?;
+            }
+            Event::Heartbeat => {
+                let sequence_number = session.lock().sequence_number;
+                heartbeat_sender.send(payload::heartbeat(sequence_number))?;
+            }
+            Event::InvalidSession(resumable) => {
+                let wait = rand::thread_rng().gen_range(1000..=5000);
+                tokio::time::sleep(Duration::from_millis(wait)).await;
+                return Ok(resumable);
+            }
+            Event::Reconnect => return Ok(true),
+            Event::Hello(hello_event) => {
+                if let Some(interval_sender) = interval_sender.take() {
+                    interval_sender.send(hello_event.heartbeat_interval).ok();
+                }
+            }
+            Event::HeartbeatAck => {
+                if let Some(sent_at) = pending_heartbeat.lock().take() {
+                    Latency::update(&mut latency.lock(), sent_at.elapsed());
+                }
+            }
+            Event::Unknown(x) => debug!(event = ?x, "unknown gateway event"),
+        }
+    }
+    Ok(true)
+}
+
+async fn heartbeat(
+    interval_receiver: oneshot::Receiver<Duration>,
+    heartbeat_sender: UnboundedSender<Value>,
+    session: Arc<Mutex<Session>>,
+    pending_heartbeat: Arc<Mutex<Option<Instant>>>,
+) -> Result<bool> {
+    let heartbeat_interval = match interval_receiver.await {
+        Ok(heartbeat_interval) => heartbeat_interval,
+        Err(_) => return Ok(true),
+    };
+    let jitter = rand::thread_rng().gen_range(0.0..1.0);
+    tokio::time::sleep(heartbeat_interval.mul_f64(jitter)).await;
+    loop {
+        if pending_heartbeat.lock().replace(Instant::now()).is_some() {
+            warn!("heartbeat ack not received, reconnecting");
+            return Ok(true);
+        }
+        trace!("sending heartbeat");
+        let sequence_number = session.lock().sequence_number;
+        heartbeat_sender.send(payload::heartbeat(sequence_number))?;
+        tokio::time::sleep(heartbeat_interval).await;
+    }
+}
+
+async fn write(
+    mut writer: AsyncGatewayWriter,
+    mut heartbeat_receiver: UnboundedReceiver<Value>,
+    commands: Arc<AsyncMutex<UnboundedReceiver<Value>>>,
+) -> Result<bool> {
+    let mut commands = commands.lock().await;
+    // A command held back by the rate limit.
+    let mut queued = None;
+    loop {
+        let delay = match queued.take() {
+            Some(payload) => match writer.command_delay() {
+                Some(delay) => {
+                    queued = Some(payload);
+                    delay
+                }
+                None => {
+                    writer.send_command(payload).await?;
+                    continue;
+                }
+            },
+            None => Duration::ZERO,
+        };
+        // Heartbeats go first so that a burst of commands can't delay them.
+        tokio::select! {
+            biased;
+            Some(payload) = heartbeat_receiver.recv() => writer.send(payload).await?,
+            Some(payload) = commands.recv(), if queued.is_none() => queued = Some(payload),
+            () = tokio::time::sleep(delay), if queued.is_some() => (),
+            else => return Ok(true),
+        }
+    }
+}
@@ -1,17 +1,22 @@
 use std::sync::Arc;
 
-use parking_lot::Mutex;
-
-use super::{Callback, Client, Context};
-use crate::gateway::{Intents, Ready};
-use crate::model::Message;
+use super::event_handler::Callbacks;
+use super::{Client, DispatchPolicy, EventHandler, ShardManager, ShutdownHandle};
+use crate::gateway::{Intents, Shard};
+use crate::http::Http;
+use crate::Config;
 
 #[derive(Default)]
 pub struct ClientBuilder<'a> {
     token: Option<String>,
+    config: Config,
     intents: Intents,
-    on_ready: Option<Callback<'a, Ready>>,
-    on_message_create: Option<Callback<'a, Message>>,
+    shard: Option<Shard>,
+    shard_count: Option<u64>,
+    // The `on_*` methods are generated in `event_handler`.
+    pub(super) callbacks: Callbacks<'a>,
+    dispatch_policy: DispatchPolicy,
+    shutdown_handle: ShutdownHandle,
 }
 
 impl<'a> ClientBuilder<'a> {
@@ -20,18 +25,29 @@ impl<'a> ClientBuilder<'a> {
     }
 
     pub fn build(self) -> Client<'a> {
+        let token = Arc::<str>::from(self.token.unwrap());
+        let http = Http::with_config(token.clone(), self.config.clone());
         Client {
-            token: self.token.unwrap(),
-            intents: self.intents,
-            on_ready: self
-                .on_ready
-                .unwrap_or_else(|| Arc::new(Mutex::new(|_, _| ()))),
-            on_message_create: self
-                .on_message_create
-                .unwrap_or_else(|| Arc::new(Mutex::new(|_, _| ()))),
+            http: http.clone(),
+            shard: self.shard,
+            shard_count: self.shard_count,
+            shard_manager: ShardManager::new(
+                token.into(),
+                http,
+                self.config,
+                self.intents,
+                self.shutdown_handle,
+            ),
+            event_handler: Box::new(self.callbacks),
+            dispatch_policy: self.dispatch_policy,
         }
     }
 
+    // Can be taken before the client is built, e.g. for a signal handler.
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown_handle.clone()
+    }
+
     pub fn with_bot_token(mut self, token: &str) -> Self {
         self.token = Some(format!("Bot {}", token));
         self
@@ -42,24 +58,34 @@ impl<'a> ClientBuilder<'a> {
         self
     }
 
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
     pub fn intents(mut self, intents: Intents) -> Self {
         self.intents = intents;
         self
     }
 
-    pub fn on_ready<F>(mut self, f: F) -> Self
-    where
-        F: FnMut(Context, Ready) + 'a,
-    {
-        self.on_ready = Some(Arc::new(Mutex::new(f)));
+    pub fn shard(mut self, shard_id: u64, shard_count: u64) -> Self {
+        self.shard = Some(Shard::new(shard_id, shard_count));
+        self
+    }
+
+    pub fn shard_count(mut self, shard_count: u64) -> Self {
+        self.shard_count = Some(shard_count);
+        self
+    }
+
+    pub fn dispatch_policy(mut self, dispatch_policy: DispatchPolicy) -> Self {
+        self.dispatch_policy = dispatch_policy;
         self
     }
 
-    pub fn on_message_create<F>(mut self, f: F) -> Self
-    where
-        F: FnMut(Context, Message) + 'a,
-    {
-        self.on_message_create = Some(Arc::new(Mutex::new(f)));
+    // Runs before the `on_*` callbacks of the same event.
+    pub fn event_handler<H: EventHandler + 'a>(mut self, event_handler: H) -> Self {
+        self.callbacks.handler = Some(Box::new(event_handler));
         self
     }
 }
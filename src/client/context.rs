@@ -1,8 +1,5 @@
-use std::sync::Arc;
-
-use parking_lot::Mutex;
-
-use crate::gateway::{Gateway, Status};
+use super::{Latency, ShardManager, ShutdownHandle};
+use crate::gateway::{GatewaySender, Shard, Status};
 use crate::http::Http;
 use crate::model::Activity;
 use crate::Result;
@@ -10,19 +7,45 @@ use crate::Result;
 #[derive(Debug, Clone)]
 pub struct Context {
     http: Http,
-    gateway: Arc<Mutex<Gateway>>,
+    gateway: GatewaySender,
+    shard: Shard,
+    shard_manager: ShardManager,
 }
 
 impl Context {
-    pub fn new(token: Arc<str>, gateway: Arc<Mutex<Gateway>>) -> Self {
+    pub fn new(
+        http: Http,
+        gateway: GatewaySender,
+        shard: Shard,
+        shard_manager: ShardManager,
+    ) -> Self {
         Self {
-            http: Http::new(token),
+            http,
             gateway,
+            shard,
+            shard_manager,
         }
     }
 
+    pub fn shard(&self) -> Shard {
+        self.shard
+    }
+
+    // Heartbeat round-trip time of the shard, once it has been measured.
+    pub fn latency(&self) -> Option<Latency> {
+        self.shard_manager.latency(self.shard.id)
+    }
+
+    pub fn shard_manager(&self) -> &ShardManager {
+        &self.shard_manager
+    }
+
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shard_manager.shutdown_handle()
+    }
+
     pub fn presence_update(&self, status: Status, activity: Option<Activity>) -> Result<()> {
-        self.gateway.lock().presence_update(status, activity)
+        self.gateway.presence_update(status, activity)
     }
 }
 
@@ -32,7 +55,7 @@ impl AsRef<Http> for Context {
     }
 }
 
-impl<'a> std::ops::Deref for Context {
+impl std::ops::Deref for Context {
     type Target = Http;
 
     fn deref(&self) -> &Self::Target {
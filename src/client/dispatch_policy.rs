@@ -0,0 +1,51 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DispatchOrdering {
+    // Events are handed to whichever worker is free.
+    Unordered,
+    // Events of the same guild are handled one at a time, in order.
+    PerGuild,
+    // Events of the same channel are handled one at a time, in order. Events
+    // without a channel fall back to their guild.
+    PerChannel,
+}
+
+#[derive(Debug, Clone)]
+pub struct DispatchPolicy {
+    pub(crate) workers: usize,
+    pub(crate) queue_size: usize,
+    pub(crate) ordering: DispatchOrdering,
+}
+
+impl Default for DispatchPolicy {
+    fn default() -> Self {
+        Self {
+            workers: 1,
+            queue_size: 256,
+            ordering: DispatchOrdering::Unordered,
+        }
+    }
+}
+
+impl DispatchPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    // Capacity of the queue between the shards and the dispatcher, and of
+    // each worker queue. The shards stop reading from the gateway (but keep
+    // heartbeating) while it is full.
+    pub fn queue_size(mut self, queue_size: usize) -> Self {
+        self.queue_size = queue_size.max(1);
+        self
+    }
+
+    pub fn ordering(mut self, ordering: DispatchOrdering) -> Self {
+        self.ordering = ordering;
+        self
+    }
+}
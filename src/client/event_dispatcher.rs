@@ -0,0 +1,134 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::panic::{self, AssertUnwindSafe};
+
+use crossbeam_channel::Receiver;
+use tracing::{debug_span, error, Span};
+
+use super::event_handler::dispatch;
+use super::{Context, DispatchOrdering, DispatchPolicy, EventHandler, ShardManager};
+use crate::gateway::{DispatchEvent, DispatchEventKind, GatewaySender, Shard};
+use crate::http::Http;
+use crate::Result;
+
+#[derive(Debug)]
+pub struct ShardEvent {
+    pub shard: Shard,
+    pub sender: GatewaySender,
+    pub event: DispatchEvent,
+}
+
+struct Job {
+    context: Context,
+    kind: DispatchEventKind,
+    span: Span,
+}
+
+pub struct EventDispatcher<'a> {
+    http: Http,
+    event_receiver: Receiver<ShardEvent>,
+    shard_manager: ShardManager,
+    event_handler: Box<dyn EventHandler + 'a>,
+    dispatch_policy: DispatchPolicy,
+}
+
+impl<'a> EventDispatcher<'a> {
+    pub fn new(
+        http: Http,
+        event_receiver: Receiver<ShardEvent>,
+        shard_manager: ShardManager,
+        event_handler: Box<dyn EventHandler + 'a>,
+        dispatch_policy: DispatchPolicy,
+    ) -> Self {
+        Self {
+            http,
+            event_receiver,
+            shard_manager,
+            event_handler,
+            dispatch_policy,
+        }
+    }
+
+    // Returns once every shard has stopped and the workers are done with the
+    // queued events. The shards are stopped if it returns early.
+    pub fn run(self) -> Result {
+        let workers = self.dispatch_policy.workers;
+        let queue_size = self.dispatch_policy.queue_size;
+        // Unordered events share a single queue, ordered events are pinned to
+        // a worker so that events with the same key never run concurrently.
+        let (senders, receivers): (Vec<_>, Vec<_>) = match self.dispatch_policy.ordering {
+            DispatchOrdering::Unordered => {
+                let (sender, receiver) = crossbeam_channel::bounded(queue_size);
+                (vec![sender], vec![receiver; workers])
+            }
+            _ => (0..workers)
+                .map(|_| crossbeam_channel::bounded(queue_size))
+                .unzip(),
+        };
+        let event_handler = &*self.event_handler;
+        let result = crossbeam_utils::thread::scope(|s| {
+            for receiver in receivers {
+                s.spawn(move |_| {
+                    for Job {
+                        context,
+                        kind,
+                        span,
+                    } in receiver
+                    {
+                        let _enter = span.enter();
+                        // A panicking handler must not take the worker down
+                        // with it.
+                        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                            dispatch(event_handler, context, kind)
+                        }));
+                        if result.is_err() {
+                            error!("event handler panicked");
+                        }
+                    }
+                });
+            }
+            for ShardEvent {
+                shard,
+                sender,
+                event,
+            } in &self.event_receiver
+            {
+                let worker = &senders[self.worker(shard, &event.kind) % senders.len()];
+                let shard = match event.kind.guild_id() {
+                    Some(guild_id) => Shard::for_guild(guild_id, shard.count),
+                    None => shard,
+                };
+                worker.send(Job {
+                    context: Context::new(
+                        self.http.clone(),
+                        sender,
+                        shard,
+                        self.shard_manager.clone(),
+                    ),
+                    kind: event.kind,
+                    span: debug_span!("dispatch", shard = shard.id, seq = event.sequence_number),
+                })?;
+            }
+            drop(senders);
+            Ok(())
+        });
+        // Nothing is left to handle the events of the shards.
+        self.shard_manager.stop();
+        result.unwrap_or_else(|err| panic::resume_unwind(err))
+    }
+
+    fn worker(&self, shard: Shard, kind: &DispatchEventKind) -> usize {
+        let key = match self.dispatch_policy.ordering {
+            DispatchOrdering::Unordered => return 0,
+            DispatchOrdering::PerGuild => kind.guild_id().map(u64::from),
+            DispatchOrdering::PerChannel => kind
+                .channel_id()
+                .map(u64::from)
+                .or_else(|| kind.guild_id().map(u64::from)),
+        }
+        .unwrap_or(shard.id);
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        (hasher.finish() % self.dispatch_policy.workers as u64) as usize
+    }
+}
@@ -1,49 +1,234 @@
+#[cfg(feature = "async")]
+use std::future::Future;
 use std::sync::Arc;
 
-use crossbeam_channel::Receiver;
+#[cfg(feature = "async")]
+use futures_util::future::BoxFuture;
 use parking_lot::Mutex;
 
-use super::{Callback, Context};
-use crate::gateway::{DispatchEvent, DispatchEventKind, Gateway, Ready};
-use crate::model::Message;
-use crate::Result;
-
-pub struct EventHandler<'a> {
-    token: Arc<str>,
-    event_receiver: Receiver<DispatchEvent>,
-    gateway: Arc<Mutex<Gateway>>,
-    on_ready: Callback<'a, Ready>,
-    on_message_create: Callback<'a, Message>,
-}
+#[cfg(feature = "async")]
+use super::{AsyncCallback, AsyncClientBuilder, AsyncContext};
+use super::{Callback, ClientBuilder, Context};
+use crate::gateway::{
+    ChannelPinsUpdate, DispatchEventKind, GuildBan, GuildEmojisUpdate, GuildIntegration,
+    GuildIntegrationsUpdate, GuildMemberAdd, GuildMemberRemove, GuildMemberUpdate,
+    GuildMembersChunk, GuildRole, GuildRoleDelete, IntegrationDelete, InviteCreate, InviteDelete,
+    MessageDelete, MessageDeleteBulk, MessageReactionAdd, MessageReactionRemove,
+    MessageReactionRemoveAll, MessageReactionRemoveEmoji, MessageUpdate, PresenceUpdate, RawEvent,
+    Ready, ThreadDelete, ThreadListSync, ThreadMemberUpdate, ThreadMembersUpdate, TypingStart,
+    VoiceServerUpdate, WebhooksUpdate,
+};
+use crate::model::{Channel, Guild, Interaction, Message, UnavailableGuild, User, VoiceState};
+
+// Generates an `EventHandler` method, a callback slot and a `ClientBuilder`
+// method for each event, and the same for `AsyncClientBuilder`. Events under `boxed` are boxed in `DispatchEventKind`
+// and are unboxed before being handed to the handler.
+macro_rules! event_handler {
+    (
+        events { $($name:ident: $kind:ident($t:ty),)* }
+        boxed { $($boxed_name:ident: $boxed_kind:ident($boxed_t:ty),)* }
+    ) => {
+        // Handlers may run on several worker threads at once, see
+        // `DispatchPolicy`.
+        pub trait EventHandler: Send + Sync {
+            fn on_resumed(&self, _ctx: Context) {}
 
-impl<'a> EventHandler<'a> {
-    pub fn new(
-        token: Arc<str>,
-        event_receiver: Receiver<DispatchEvent>,
-        gateway: Arc<Mutex<Gateway>>,
-        on_ready: Callback<'a, Ready>,
-        on_message_create: Callback<'a, Message>,
-    ) -> Self {
-        Self {
-            token,
-            event_receiver,
-            gateway,
-            on_ready,
-            on_message_create,
+            $(fn $name(&self, _ctx: Context, _event: $t) {})*
+
+            $(fn $boxed_name(&self, _ctx: Context, _event: $boxed_t) {})*
+        }
+
+        pub(crate) fn dispatch(handler: &dyn EventHandler, context: Context, kind: DispatchEventKind) {
+            match kind {
+                DispatchEventKind::Resumed => handler.on_resumed(context),
+                $(DispatchEventKind::$kind(event) => handler.$name(context, event),)*
+                $(DispatchEventKind::$boxed_kind(event) => handler.$boxed_name(context, *event),)*
+            }
+        }
+
+        // The event handler and closures registered on `ClientBuilder`.
+        #[derive(Default)]
+        pub(crate) struct Callbacks<'a> {
+            pub handler: Option<Box<dyn EventHandler + 'a>>,
+            on_resumed: Option<Callback<'a, ()>>,
+            $($name: Option<Callback<'a, $t>>,)*
+            $($boxed_name: Option<Callback<'a, $boxed_t>>,)*
         }
-    }
 
-    pub fn run(self) -> Result {
-        let context = Context::new(self.token.clone(), self.gateway.clone());
-        loop {
-            let event = self.event_receiver.recv()?;
-            match event.kind {
-                DispatchEventKind::Ready(ready) => (self.on_ready.lock())(context.clone(), ready),
-                DispatchEventKind::MessageCreate(message) => {
-                    (self.on_message_create.lock())(context.clone(), *message)
+        impl EventHandler for Callbacks<'_> {
+            fn on_resumed(&self, context: Context) {
+                call(&self.handler, &self.on_resumed, context, (), |h, ctx, ()| {
+                    h.on_resumed(ctx)
+                })
+            }
+
+            $(fn $name(&self, context: Context, event: $t) {
+                call(&self.handler, &self.$name, context, event, |h, ctx, event| {
+                    h.$name(ctx, event)
+                })
+            })*
+
+            $(fn $boxed_name(&self, context: Context, event: $boxed_t) {
+                call(&self.handler, &self.$boxed_name, context, event, |h, ctx, event| {
+                    h.$boxed_name(ctx, event)
+                })
+            })*
+        }
+
+        impl<'a> ClientBuilder<'a> {
+            pub fn on_resumed<F>(mut self, mut f: F) -> Self
+            where
+                F: FnMut(Context) + Send + 'a,
+            {
+                self.callbacks.on_resumed = Some(Arc::new(Mutex::new(move |ctx, ()| f(ctx))));
+                self
+            }
+
+            $(pub fn $name<F>(mut self, f: F) -> Self
+            where
+                F: FnMut(Context, $t) + Send + 'a,
+            {
+                self.callbacks.$name = Some(Arc::new(Mutex::new(f)));
+                self
+            })*
+
+            $(pub fn $boxed_name<F>(mut self, f: F) -> Self
+            where
+                F: FnMut(Context, $boxed_t) + Send + 'a,
+            {
+                self.callbacks.$boxed_name = Some(Arc::new(Mutex::new(f)));
+                self
+            })*
+        }
+
+        // The closures registered on `AsyncClientBuilder`.
+        #[cfg(feature = "async")]
+        #[derive(Default)]
+        pub(crate) struct AsyncCallbacks {
+            on_resumed: Option<AsyncCallback<()>>,
+            $($name: Option<AsyncCallback<$t>>,)*
+            $($boxed_name: Option<AsyncCallback<$boxed_t>>,)*
+        }
+
+        #[cfg(feature = "async")]
+        impl AsyncCallbacks {
+            // `None` when no closure is registered for the event.
+            pub fn dispatch(
+                &self,
+                context: AsyncContext,
+                kind: DispatchEventKind,
+            ) -> Option<BoxFuture<'static, ()>> {
+                match kind {
+                    DispatchEventKind::Resumed => self.on_resumed.as_ref().map(|f| f(context, ())),
+                    $(DispatchEventKind::$kind(event) => self.$name.as_ref().map(|f| f(context, event)),)*
+                    $(DispatchEventKind::$boxed_kind(event) => {
+                        self.$boxed_name.as_ref().map(|f| f(context, *event))
+                    })*
                 }
-                DispatchEventKind::Unknown(_) => continue,
-            };
+            }
+        }
+
+        #[cfg(feature = "async")]
+        impl AsyncClientBuilder {
+            pub fn on_resumed<F, Fut>(mut self, f: F) -> Self
+            where
+                F: Fn(AsyncContext) -> Fut + Send + Sync + 'static,
+                Fut: Future<Output = ()> + Send + 'static,
+            {
+                self.callbacks.on_resumed = Some(Arc::new(move |ctx, ()| Box::pin(f(ctx))));
+                self
+            }
+
+            $(pub fn $name<F, Fut>(mut self, f: F) -> Self
+            where
+                F: Fn(AsyncContext, $t) -> Fut + Send + Sync + 'static,
+                Fut: Future<Output = ()> + Send + 'static,
+            {
+                self.callbacks.$name = Some(Arc::new(move |ctx, event| Box::pin(f(ctx, event))));
+                self
+            })*
+
+            $(pub fn $boxed_name<F, Fut>(mut self, f: F) -> Self
+            where
+                F: Fn(AsyncContext, $boxed_t) -> Fut + Send + Sync + 'static,
+                Fut: Future<Output = ()> + Send + 'static,
+            {
+                self.callbacks.$boxed_name = Some(Arc::new(move |ctx, event| Box::pin(f(ctx, event))));
+                self
+            })*
         }
+    };
+}
+
+// Runs the event handler first and then the closure, cloning the event only
+// when both are set.
+fn call<'a, T: Clone>(
+    handler: &Option<Box<dyn EventHandler + 'a>>,
+    callback: &Option<Callback<'a, T>>,
+    context: Context,
+    event: T,
+    f: impl FnOnce(&(dyn EventHandler + 'a), Context, T),
+) {
+    match (handler, callback) {
+        (Some(handler), Some(callback)) => {
+            f(&**handler, context.clone(), event.clone());
+            (callback.lock())(context, event)
+        }
+        (Some(handler), None) => f(&**handler, context, event),
+        (None, Some(callback)) => (callback.lock())(context, event),
+        (None, None) => (),
+    }
+}
+
+event_handler! {
+    events {
+        on_ready: Ready(Ready),
+        on_channel_pins_update: ChannelPinsUpdate(ChannelPinsUpdate),
+        on_thread_delete: ThreadDelete(ThreadDelete),
+        on_thread_list_sync: ThreadListSync(ThreadListSync),
+        on_thread_member_update: ThreadMemberUpdate(ThreadMemberUpdate),
+        on_thread_members_update: ThreadMembersUpdate(ThreadMembersUpdate),
+        on_guild_delete: GuildDelete(UnavailableGuild),
+        on_ban_add: GuildBanAdd(GuildBan),
+        on_ban_remove: GuildBanRemove(GuildBan),
+        on_guild_emojis_update: GuildEmojisUpdate(GuildEmojisUpdate),
+        on_guild_integrations_update: GuildIntegrationsUpdate(GuildIntegrationsUpdate),
+        on_member_leave: GuildMemberRemove(GuildMemberRemove),
+        on_members_chunk: GuildMembersChunk(GuildMembersChunk),
+        on_role_create: GuildRoleCreate(GuildRole),
+        on_role_update: GuildRoleUpdate(GuildRole),
+        on_role_delete: GuildRoleDelete(GuildRoleDelete),
+        on_integration_delete: IntegrationDelete(IntegrationDelete),
+        on_invite_delete: InviteDelete(InviteDelete),
+        on_message_delete: MessageDelete(MessageDelete),
+        on_message_delete_bulk: MessageDeleteBulk(MessageDeleteBulk),
+        on_reaction_remove: MessageReactionRemove(MessageReactionRemove),
+        on_reaction_remove_all: MessageReactionRemoveAll(MessageReactionRemoveAll),
+        on_reaction_remove_emoji: MessageReactionRemoveEmoji(MessageReactionRemoveEmoji),
+        on_user_update: UserUpdate(User),
+        on_voice_server_update: VoiceServerUpdate(VoiceServerUpdate),
+        on_webhooks_update: WebhooksUpdate(WebhooksUpdate),
+        on_raw_event: Unknown(RawEvent),
+    }
+    boxed {
+        on_channel_create: ChannelCreate(Channel),
+        on_channel_update: ChannelUpdate(Channel),
+        on_channel_delete: ChannelDelete(Channel),
+        on_thread_create: ThreadCreate(Channel),
+        on_thread_update: ThreadUpdate(Channel),
+        on_guild_create: GuildCreate(Guild),
+        on_guild_update: GuildUpdate(Guild),
+        on_member_join: GuildMemberAdd(GuildMemberAdd),
+        on_member_update: GuildMemberUpdate(GuildMemberUpdate),
+        on_integration_create: IntegrationCreate(GuildIntegration),
+        on_integration_update: IntegrationUpdate(GuildIntegration),
+        on_interaction_create: InteractionCreate(Interaction),
+        on_invite_create: InviteCreate(InviteCreate),
+        on_message_create: MessageCreate(Message),
+        on_message_update: MessageUpdate(MessageUpdate),
+        on_reaction_add: MessageReactionAdd(MessageReactionAdd),
+        on_presence_update: PresenceUpdate(PresenceUpdate),
+        on_typing_start: TypingStart(TypingStart),
+        on_voice_state_update: VoiceStateUpdate(VoiceState),
     }
 }
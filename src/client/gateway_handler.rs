@@ -1,131 +1,215 @@
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use crossbeam_channel::Sender;
+use crossbeam_channel::{SendTimeoutError, Sender};
 use parking_lot::Mutex;
 use rand::Rng;
+use tracing::{debug, field, info, info_span, trace, warn, Span};
 
-use crate::gateway::{DispatchEvent, Event, Gateway};
-use crate::Result;
+use super::session::Session;
+use super::{ShardEvent, ShardManager, ShardStatus};
+use crate::gateway::{Event, Gateway, Shard};
+use crate::token::Token;
+use crate::{Error, Result};
 
 #[derive(Debug)]
 pub struct GatewayHandler {
-    token: Arc<str>,
-    event_sender: Sender<DispatchEvent>,
-    gateway: Arc<Mutex<Gateway>>,
-    sequence_number: Option<u64>,
+    token: Token,
+    event_sender: Sender<ShardEvent>,
+    gateway: Gateway,
+    shard: Shard,
+    shard_manager: ShardManager,
+    session: Arc<Mutex<Session>>,
     last_heartbeat: Instant,
     heartbeat_interval: Option<Duration>,
-    last_heartbeat_ack: bool,
-    session_id: Option<String>,
+    // When the oldest unacked heartbeat was sent, for the latency.
+    pending_heartbeat: Option<Instant>,
+    span: Span,
 }
 
 impl GatewayHandler {
     pub fn new(
-        token: Arc<str>,
-        event_sender: Sender<DispatchEvent>,
-        gateway: Arc<Mutex<Gateway>>,
+        token: Token,
+        event_sender: Sender<ShardEvent>,
+        gateway: Gateway,
+        shard_manager: ShardManager,
+        session: Arc<Mutex<Session>>,
     ) -> Self {
+        let shard = gateway.shard();
         Self {
             token,
             event_sender,
             gateway,
-            sequence_number: None,
-            session_id: None,
+            shard,
+            shard_manager,
+            session,
             last_heartbeat: Instant::now(),
-            last_heartbeat_ack: false,
+            pending_heartbeat: None,
             heartbeat_interval: None,
+            span: info_span!("gateway", shard = shard.id, seq = field::Empty),
         }
     }
 
-    pub fn reconnect(&mut self) -> Result {
-        self.gateway.lock().reconnect()
+    // Replaces the connection, resuming the session if `resume` is set and
+    // there is one, and identifying otherwise.
+    pub fn reconnect(&mut self, resume: bool) -> Result {
+        let resume_url = {
+            let mut session = self.session.lock();
+            if !resume {
+                *session = Session::default();
+            }
+            session.resume_url().map(str::to_owned)
+        };
+        match resume_url.or_else(|| self.shard_manager.gateway_url()) {
+            Some(url) => self.gateway.reconnect_to(&url)?,
+            None => self.gateway.reconnect()?,
+        }
+        self.pending_heartbeat = None;
+        if self.session.lock().can_resume() {
+            self.resume()
+        } else {
+            self.identify()
+        }
     }
 
     pub fn run(mut self) -> Result {
-        self.identify()?;
-        loop {
+        let span = self.span.clone();
+        let _enter = span.enter();
+        if self.session.lock().can_resume() {
+            self.resume()?;
+        } else {
+            self.identify()?;
+        }
+        while !self.shard_manager.is_stopped() {
             if let Some(heartbeat_interval) = self.heartbeat_interval {
-                let now = Instant::now();
-                if self.last_heartbeat + heartbeat_interval <= now {
-                    if !self.last_heartbeat_ack {
-                        eprintln!("HeartbeatAck not received, reconnecting");
-                        self.reconnect()?;
-                        self.resume()?;
+                if self.last_heartbeat + heartbeat_interval <= Instant::now() {
+                    if self.pending_heartbeat.is_some() {
+                        warn!("heartbeat ack not received, reconnecting");
+                        self.reconnect(true)?;
                     }
                     self.heartbeat()?;
-                    self.last_heartbeat = now;
-                    self.last_heartbeat_ack = false;
                 }
             }
-            let events = self.get_events()?;
+            let events = match self.wait_events() {
+                Err(Error::GatewayClosed { code, reason }) if code.can_reconnect() => {
+                    warn!(?code, reason, "gateway closed, reconnecting");
+                    self.reconnect(code.can_resume())?;
+                    continue;
+                }
+                result => result?,
+            };
             for event in events {
                 self.handle_event(event)?;
             }
         }
+        info!("shutting down");
+        self.gateway.shutdown()
     }
 
     fn handle_event(&mut self, event: Event) -> Result {
         match event {
             Event::Dispatch(dispatch_event) => {
-                if let Some(ready) = dispatch_event.kind.as_ready() {
-                    self.session_id = Some(ready.session_id.clone());
+                self.session.lock().update(&dispatch_event);
+                if dispatch_event.kind.as_ready().is_some() {
                     self.heartbeat()?;
                 }
-                self.sequence_number = Some(dispatch_event.sequence_number);
-                self.event_sender.send(dispatch_event)?;
+                self.span.record("seq", dispatch_event.sequence_number);
+                self.shard_manager
+                    .set_status(self.shard, ShardStatus::Connected);
+                let event = ShardEvent {
+                    shard: self.shard,
+                    sender: self.gateway.sender(),
+                    event: dispatch_event,
+                };
+                self.dispatch(event)?;
             }
             Event::Heartbeat => self.heartbeat()?,
             Event::InvalidSession(resumable) => {
                 let wait = rand::thread_rng().gen_range(1000..=5000);
                 std::thread::sleep(Duration::from_millis(wait));
-                self.reconnect()?;
-                if resumable {
-                    self.resume()?;
-                } else {
-                    self.identify()?;
-                }
-            }
-            Event::Reconnect => {
-                self.reconnect()?;
-                self.resume()?;
+                self.reconnect(resumable)?;
             }
+            Event::Reconnect => self.reconnect(true)?,
             Event::Hello(hello_event) => {
                 self.heartbeat_interval = Some(hello_event.heartbeat_interval);
             }
-            Event::HeartbeatAck => self.last_heartbeat_ack = true,
-            Event::Unknown(x) => println!("unknown event: {:?}", x),
+            Event::HeartbeatAck => {
+                if let Some(sent_at) = self.pending_heartbeat.take() {
+                    self.shard_manager
+                        .record_latency(self.shard, sent_at.elapsed());
+                }
+            }
+            Event::Unknown(x) => debug!(event = ?x, "unknown gateway event"),
         }
         Ok(())
     }
 
-    fn get_events(&mut self) -> Result<Vec<Event>> {
-        self.gateway.lock().get_events()
+    // Blocks while the dispatch queue is full, which stops reading from the
+    // gateway, but keeps heartbeating so that the connection stays alive.
+    // Acks are not read meanwhile, so they are not checked either.
+    fn dispatch(&mut self, mut event: ShardEvent) -> Result {
+        loop {
+            let timeout = match self.heartbeat_timeout() {
+                Some(timeout) => timeout,
+                None => return Ok(self.event_sender.send(event)?),
+            };
+            match self.event_sender.send_timeout(event, timeout) {
+                Ok(()) => return Ok(()),
+                Err(SendTimeoutError::Timeout(unsent)) => {
+                    debug!("dispatch queue full, heartbeating");
+                    event = unsent;
+                    self.heartbeat()?;
+                }
+                Err(SendTimeoutError::Disconnected(_)) => return Err(Error::Send),
+            }
+        }
+    }
+
+    // Time left until the next heartbeat is due.
+    fn heartbeat_timeout(&self) -> Option<Duration> {
+        self.heartbeat_interval.map(|heartbeat_interval| {
+            (self.last_heartbeat + heartbeat_interval).saturating_duration_since(Instant::now())
+        })
+    }
+
+    // Waits for events until the next heartbeat is due.
+    fn wait_events(&mut self) -> Result<Vec<Event>> {
+        let timeout = self.heartbeat_timeout();
+        self.gateway.wait_events(timeout)
     }
 
     #[inline]
     fn heartbeat(&mut self) -> Result {
-        println!("heartbeat");
+        trace!("sending heartbeat");
         let now = Instant::now();
-        self.gateway.lock().heartbeat(self.sequence_number)?;
+        let sequence_number = self.session.lock().sequence_number;
+        self.gateway.heartbeat(sequence_number)?;
         self.last_heartbeat = now;
-        self.last_heartbeat_ack = false;
+        self.pending_heartbeat.get_or_insert(now);
         Ok(())
     }
 
     #[inline]
     fn identify(&mut self) -> Result {
-        println!("identify");
-        self.gateway.lock().identify(&self.token)?;
+        if !self.shard_manager.wait_identify(self.shard) {
+            return Ok(());
+        }
+        info!("identifying");
+        self.gateway.identify(&self.token)?;
         Ok(())
     }
 
     #[inline]
     fn resume(&mut self) -> Result {
-        println!("resume");
+        self.shard_manager
+            .set_status(self.shard, ShardStatus::Resuming);
+        info!("resuming session");
+        let (session_id, sequence_number) = {
+            let session = self.session.lock();
+            (session.session_id.clone(), session.sequence_number)
+        };
         self.gateway
-            .lock()
-            .resume(&self.token, &self.session_id, self.sequence_number)?;
+            .resume(&self.token, &session_id, sequence_number)?;
         Ok(())
     }
 }
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use tracing::warn;
+
+use super::ShutdownHandle;
+use crate::gateway::Shard;
+use crate::model::SessionStartLimit;
+
+const IDENTIFY_INTERVAL: Duration = Duration::from_secs(5);
+const SESSION_START_LIMIT_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+// Shards whose ids are equal modulo `max_concurrency` share a bucket, and
+// identifies within a bucket must be at least five seconds apart. Identifies
+// are also held back while the session start limit is exhausted, since going
+// over it gets the token reset.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct IdentifyQueue {
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    max_concurrency: u64,
+    buckets: HashMap<u64, Instant>,
+    session_start_limit: Option<SessionLimit>,
+}
+
+#[derive(Debug)]
+struct SessionLimit {
+    total: u64,
+    remaining: u64,
+    reset_at: Instant,
+}
+
+impl IdentifyQueue {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn set_session_start_limit(&self, session_start_limit: &SessionStartLimit) {
+        let mut inner = self.inner.lock();
+        inner.max_concurrency = session_start_limit.max_concurrency;
+        inner.session_start_limit = Some(SessionLimit {
+            total: session_start_limit.total,
+            remaining: session_start_limit.remaining,
+            reset_at: Instant::now() + session_start_limit.reset_after,
+        });
+    }
+
+    pub(crate) fn session_start_limit(&self) -> Option<SessionStartLimit> {
+        let inner = self.inner.lock();
+        let limit = inner.session_start_limit.as_ref()?;
+        Some(SessionStartLimit {
+            total: limit.total,
+            remaining: limit.remaining,
+            reset_after: limit.reset_at.saturating_duration_since(Instant::now()),
+            max_concurrency: inner.max_concurrency.max(1),
+        })
+    }
+
+    // Blocks until `shard` is allowed to identify. Returns `false`, without
+    // taking a slot, if shut down meanwhile.
+    pub(crate) fn wait(&self, shard: Shard, shutdown_handle: &ShutdownHandle) -> bool {
+        while !shutdown_handle.is_shutdown() {
+            match self.reserve(shard) {
+                Some(delay) => {
+                    shutdown_handle.sleep(delay);
+                }
+                None => return true,
+            }
+        }
+        false
+    }
+
+    // Takes an identify slot for `shard`, or returns how long to wait before
+    // trying again.
+    pub(crate) fn reserve(&self, shard: Shard) -> Option<Duration> {
+        let now = Instant::now();
+        let inner = &mut *self.inner.lock();
+        if let Some(limit) = &mut inner.session_start_limit {
+            if limit.reset_at <= now {
+                limit.remaining = limit.total;
+                limit.reset_at = now + SESSION_START_LIMIT_WINDOW;
+            }
+            if limit.remaining == 0 {
+                let reset_after = limit.reset_at - now;
+                warn!(
+                    ?reset_after,
+                    "session start limit exhausted, delaying identify"
+                );
+                return Some(reset_after);
+            }
+        }
+        let key = shard.id % inner.max_concurrency.max(1);
+        match inner.buckets.get(&key) {
+            Some(&last) if last + IDENTIFY_INTERVAL > now => Some(last + IDENTIFY_INTERVAL - now),
+            _ => {
+                inner.buckets.insert(key, now);
+                if let Some(limit) = &mut inner.session_start_limit {
+                    limit.remaining -= 1;
+                }
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue(remaining: u64, reset_after: Duration, max_concurrency: u64) -> IdentifyQueue {
+        let queue = IdentifyQueue::new();
+        queue.set_session_start_limit(&SessionStartLimit {
+            total: 1000,
+            remaining,
+            reset_after,
+            max_concurrency,
+        });
+        queue
+    }
+
+    fn is_spacing(delay: Option<Duration>) -> bool {
+        matches!(delay, Some(delay) if delay > IDENTIFY_INTERVAL - Duration::from_secs(1) && delay <= IDENTIFY_INTERVAL)
+    }
+
+    #[test]
+    fn spacing_within_bucket() {
+        let queue = queue(1000, SESSION_START_LIMIT_WINDOW, 1);
+        assert_eq!(queue.reserve(Shard::new(0, 2)), None);
+        assert!(is_spacing(queue.reserve(Shard::new(1, 2))));
+        assert!(is_spacing(queue.reserve(Shard::new(0, 2))));
+        // Waiting does not take a slot.
+        assert_eq!(queue.session_start_limit().unwrap().remaining, 999);
+    }
+
+    #[test]
+    fn independent_buckets() {
+        let queue = queue(1000, SESSION_START_LIMIT_WINDOW, 2);
+        assert_eq!(queue.reserve(Shard::new(0, 4)), None);
+        assert_eq!(queue.reserve(Shard::new(1, 4)), None);
+        assert!(is_spacing(queue.reserve(Shard::new(2, 4))));
+        assert!(is_spacing(queue.reserve(Shard::new(3, 4))));
+        assert_eq!(queue.session_start_limit().unwrap().remaining, 998);
+    }
+
+    #[test]
+    fn exhausted() {
+        let reset_after = Duration::from_secs(3600);
+        let queue = queue(0, reset_after, 1);
+        let delay = queue.reserve(Shard::new(0, 1)).unwrap();
+        assert!(delay > reset_after - Duration::from_secs(1) && delay <= reset_after);
+        assert_eq!(queue.session_start_limit().unwrap().remaining, 0);
+    }
+
+    #[test]
+    fn refilled_after_window() {
+        let queue = queue(0, Duration::ZERO, 1);
+        assert_eq!(queue.reserve(Shard::new(0, 1)), None);
+        let limit = queue.session_start_limit().unwrap();
+        assert_eq!(limit.remaining, 999);
+        assert!(limit.reset_after > SESSION_START_LIMIT_WINDOW - Duration::from_secs(1));
+    }
+}
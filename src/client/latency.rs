@@ -0,0 +1,22 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Latency {
+    pub last: Duration,
+    pub average: Duration,
+}
+
+impl Latency {
+    // The average is exponentially weighted with the same 1/8 gain TCP uses for
+    // round-trip times, so a single slow heartbeat doesn't swing it much.
+    pub(crate) fn update(latency: &mut Option<Self>, sample: Duration) {
+        let average = match latency {
+            Some(latency) => latency.average - latency.average / 8 + sample / 8,
+            None => sample,
+        };
+        *latency = Some(Self {
+            last: sample,
+            average,
+        });
+    }
+}
@@ -0,0 +1,31 @@
+use crate::gateway::DispatchEvent;
+
+// What is needed to resume a gateway session. It outlives individual
+// connections and gateway handlers.
+#[derive(Debug, Default)]
+pub(crate) struct Session {
+    pub(crate) session_id: Option<String>,
+    pub(crate) sequence_number: Option<u64>,
+    pub(crate) resume_gateway_url: Option<String>,
+}
+
+impl Session {
+    pub(crate) fn update(&mut self, dispatch_event: &DispatchEvent) {
+        if let Some(ready) = dispatch_event.kind.as_ready() {
+            self.session_id = Some(ready.session_id.clone());
+            self.resume_gateway_url = ready.resume_gateway_url.clone();
+        }
+        self.sequence_number = Some(dispatch_event.sequence_number);
+    }
+
+    pub(crate) fn can_resume(&self) -> bool {
+        self.session_id.is_some()
+    }
+
+    // The url to resume on, if the session can be resumed.
+    pub(crate) fn resume_url(&self) -> Option<&str> {
+        self.session_id
+            .as_ref()
+            .and(self.resume_gateway_url.as_deref())
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use parking_lot::Mutex;
+use tracing::error;
+
+use super::event_dispatcher::ShardEvent;
+use super::gateway_handler::GatewayHandler;
+use super::identify_queue::IdentifyQueue;
+use super::latency::Latency;
+use super::session::Session;
+use super::ShutdownHandle;
+use crate::gateway::{Gateway, Intents, Shard};
+use crate::http::Http;
+use crate::model::{GatewayBot, SessionStartLimit};
+use crate::token::Token;
+use crate::{Config, Error, Result};
+
+const RESTART_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShardStatus {
+    Connecting,
+    Identifying,
+    Resuming,
+    Connected,
+    Disconnected,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ShardInfo {
+    pub shard: Shard,
+    pub status: ShardStatus,
+    pub latency: Option<Latency>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ShardManager {
+    token: Token,
+    http: Http,
+    config: Config,
+    intents: Intents,
+    shards: Arc<Mutex<BTreeMap<u64, ShardInfo>>>,
+    identify_queue: IdentifyQueue,
+    shutdown_handle: ShutdownHandle,
+}
+
+impl ShardManager {
+    pub(crate) fn new(
+        token: Token,
+        http: Http,
+        config: Config,
+        intents: Intents,
+        shutdown_handle: ShutdownHandle,
+    ) -> Self {
+        Self {
+            token,
+            http,
+            config,
+            intents,
+            shards: Default::default(),
+            identify_queue: IdentifyQueue::new(),
+            shutdown_handle,
+        }
+    }
+
+    pub fn shards(&self) -> Vec<ShardInfo> {
+        self.shards.lock().values().copied().collect()
+    }
+
+    pub fn shard(&self, shard_id: u64) -> Option<ShardInfo> {
+        self.shards.lock().get(&shard_id).copied()
+    }
+
+    pub fn session_start_limit(&self) -> Option<SessionStartLimit> {
+        self.identify_queue.session_start_limit()
+    }
+
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown_handle.clone()
+    }
+
+    // Reuses the gateway url for every (re)connect instead of fetching it again.
+    pub(crate) fn set_gateway_bot(&mut self, gateway_bot: GatewayBot) {
+        self.config.gateway_url.get_or_insert(gateway_bot.url);
+        self.identify_queue
+            .set_session_start_limit(&gateway_bot.session_start_limit);
+    }
+
+    pub(crate) fn set_status(&self, shard: Shard, status: ShardStatus) {
+        self.shards
+            .lock()
+            .entry(shard.id)
+            .or_insert(ShardInfo {
+                shard,
+                status,
+                latency: None,
+            })
+            .status = status;
+    }
+
+    pub fn latency(&self, shard_id: u64) -> Option<Latency> {
+        self.shard(shard_id).and_then(|info| info.latency)
+    }
+
+    pub(crate) fn record_latency(&self, shard: Shard, sample: Duration) {
+        if let Some(info) = self.shards.lock().get_mut(&shard.id) {
+            Latency::update(&mut info.latency, sample);
+        }
+    }
+
+    // Blocks until `shard` is allowed to identify. Returns `false` if shut
+    // down meanwhile.
+    pub(crate) fn wait_identify(&self, shard: Shard) -> bool {
+        self.set_status(shard, ShardStatus::Identifying);
+        self.identify_queue.wait(shard, &self.shutdown_handle)
+    }
+
+    pub(crate) fn gateway_url(&self) -> Option<String> {
+        self.config.gateway_url.clone()
+    }
+
+    // Stops every shard, e.g. after a close code that rules out reconnecting.
+    pub(crate) fn stop(&self) {
+        self.shutdown_handle.shutdown();
+    }
+
+    pub(crate) fn is_stopped(&self) -> bool {
+        self.shutdown_handle.is_shutdown()
+    }
+
+    // Runs `shard` until the manager is stopped, starting it again whenever it
+    // dies. The session is kept across restarts so that the new handler can
+    // resume it.
+    pub(crate) fn run_shard(&self, shard: Shard, event_sender: Sender<ShardEvent>) -> Result {
+        let session = Arc::new(Mutex::new(Session::default()));
+        while !self.is_stopped() {
+            self.set_status(shard, ShardStatus::Connecting);
+            let mut config = self.config.clone();
+            if let Some(url) = session.lock().resume_url() {
+                config.gateway_url = Some(url.to_owned());
+            }
+            match Gateway::connect_with_shard(config, &self.http, self.intents, shard) {
+                Ok(gateway) => {
+                    self.shutdown_handle.register(shard.id, gateway.sender());
+                    let gateway_handler = GatewayHandler::new(
+                        self.token.clone(),
+                        event_sender.clone(),
+                        gateway,
+                        self.clone(),
+                        session.clone(),
+                    );
+                    match gateway_handler.run() {
+                        Ok(()) => (),
+                        Err(Error::GatewayClosed { code, reason }) if !code.can_reconnect() => {
+                            error!(shard = shard.id, ?code, reason, "gateway closed, stopping");
+                            self.set_status(shard, ShardStatus::Disconnected);
+                            self.stop();
+                            return Err(Error::GatewayClosed { code, reason });
+                        }
+                        Err(err) => {
+                            error!(shard = shard.id, error = ?err, "gateway handler failed")
+                        }
+                    }
+                }
+                Err(err) => {
+                    error!(shard = shard.id, error = ?err, "failed to connect to the gateway")
+                }
+            }
+            self.set_status(shard, ShardStatus::Disconnected);
+            self.shutdown_handle.sleep(RESTART_DELAY);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::{Condvar, Mutex};
+
+use crate::gateway::GatewaySender;
+
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownHandle {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    stopped: Mutex<bool>,
+    // Wakes up the shards sleeping before identifying or restarting.
+    condvar: Condvar,
+    // Used to wake up the shards blocked waiting for events.
+    senders: Mutex<HashMap<u64, GatewaySender>>,
+}
+
+impl ShutdownHandle {
+    // Closes every shard with code 1000. `Client::run` then returns `Ok(())`
+    // once the queued events have been handled.
+    pub fn shutdown(&self) {
+        *self.inner.stopped.lock() = true;
+        self.inner.condvar.notify_all();
+        for sender in self.inner.senders.lock().values() {
+            sender.wake().ok();
+        }
+    }
+
+    pub fn is_shutdown(&self) -> bool {
+        *self.inner.stopped.lock()
+    }
+
+    pub(crate) fn register(&self, shard_id: u64, sender: GatewaySender) {
+        self.inner.senders.lock().insert(shard_id, sender);
+    }
+
+    // Sleeps for `duration` unless shut down meanwhile. Returns `false` if
+    // shut down.
+    pub(crate) fn sleep(&self, duration: Duration) -> bool {
+        let deadline = Instant::now() + duration;
+        let mut stopped = self.inner.stopped.lock();
+        while !*stopped {
+            if self
+                .inner
+                .condvar
+                .wait_until(&mut stopped, deadline)
+                .timed_out()
+            {
+                break;
+            }
+        }
+        !*stopped
+    }
+}
@@ -0,0 +1,69 @@
+use crate::consts::{API_URL, API_VERSION};
+use crate::gateway::Encoding;
+use crate::http::RetryPolicy;
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub(crate) api_url: String,
+    pub(crate) api_version: u8,
+    pub(crate) gateway_url: Option<String>,
+    pub(crate) tls: bool,
+    pub(crate) compress: bool,
+    pub(crate) encoding: Encoding,
+    pub(crate) retry_policy: RetryPolicy,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            api_url: API_URL.to_string(),
+            api_version: API_VERSION,
+            gateway_url: None,
+            tls: true,
+            compress: false,
+            encoding: Encoding::Json,
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn api_url<T: ToString>(mut self, api_url: T) -> Self {
+        self.api_url = api_url.to_string().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn api_version(mut self, api_version: u8) -> Self {
+        self.api_version = api_version;
+        self
+    }
+
+    pub fn gateway_url<T: ToString>(mut self, gateway_url: T) -> Self {
+        self.gateway_url = Some(gateway_url.to_string().trim_end_matches('/').to_string());
+        self
+    }
+
+    pub fn tls(mut self, tls: bool) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    pub fn compress(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+}
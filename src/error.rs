@@ -1,22 +1,79 @@
 use std::error::Error as StdError;
 use std::fmt;
 use std::io;
+use std::time::Duration;
 
 use crossbeam_channel::{RecvError, SendError};
 use mio::net::TcpStream;
+use serde::{Deserialize, Deserializer};
 use tungstenite::handshake::client::ClientHandshake;
 use tungstenite::stream::MaybeTlsStream;
 use tungstenite::HandshakeError;
 
+use crate::gateway::CloseCode;
+
 #[derive(Debug)]
 pub enum Error {
+    Api {
+        status: u16,
+        code: JsonErrorCode,
+        message: String,
+        errors: serde_json::Value,
+    },
+    GatewayClosed {
+        code: CloseCode,
+        reason: String,
+    },
+    GatewayRateLimited {
+        retry_after: Duration,
+    },
+    InvalidRateLimit {
+        name: &'static str,
+        value: String,
+    },
     Io(io::Error),
     Json(serde_json::Error),
     Recv,
+    #[cfg(feature = "async")]
+    Reqwest(Box<reqwest::Error>),
     Send,
-    Tungstenite(tungstenite::Error),
+    Tungstenite(Box<tungstenite::Error>),
     TungsteniteHandshake(Box<HandshakeError<ClientHandshake<MaybeTlsStream<TcpStream>>>>),
-    Ureq(ureq::Error),
+    Ureq(Box<ureq::Error>),
+}
+
+impl Error {
+    pub(crate) fn from_response(status: u16, response: ureq::Response) -> Self {
+        match response.into_string() {
+            Ok(text) => Self::from_body(status, text),
+            Err(err) => err.into(),
+        }
+    }
+
+    pub(crate) fn from_body(status: u16, text: String) -> Self {
+        #[derive(Deserialize)]
+        struct Body {
+            code: JsonErrorCode,
+            message: String,
+            #[serde(default)]
+            errors: serde_json::Value,
+        }
+
+        match serde_json::from_str::<Body>(&text) {
+            Ok(body) => Self::Api {
+                status,
+                code: body.code,
+                message: body.message,
+                errors: body.errors,
+            },
+            Err(_) => Self::Api {
+                status,
+                code: JsonErrorCode::General,
+                message: text,
+                errors: serde_json::Value::Null,
+            },
+        }
+    }
 }
 
 impl StdError for Error {}
@@ -24,9 +81,32 @@ impl StdError for Error {}
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Self::Api {
+                status,
+                code,
+                message,
+                ..
+            } => write!(f, "{} {} (code {})", status, message, u32::from(*code)),
+            Self::GatewayClosed { code, reason } => write!(
+                f,
+                "gateway closed with code {} ({:?}): {}",
+                u16::from(*code),
+                code,
+                reason
+            ),
+            Self::GatewayRateLimited { retry_after } => write!(
+                f,
+                "gateway rate limit exceeded, retry after {} ms",
+                retry_after.as_millis()
+            ),
+            Self::InvalidRateLimit { name, value } => {
+                write!(f, "invalid rate limit value for {}: {:?}", name, value)
+            }
             Self::Io(err) => err.fmt(f),
             Self::Json(err) => err.fmt(f),
             Self::Recv => RecvError.fmt(f),
+            #[cfg(feature = "async")]
+            Self::Reqwest(err) => err.fmt(f),
             Self::Send => "sending on a closed channel".fmt(f),
             Self::Tungstenite(err) => err.fmt(f),
             Self::TungsteniteHandshake(err) => err.fmt(f),
@@ -59,9 +139,16 @@ impl<T> From<SendError<T>> for Error {
     }
 }
 
+#[cfg(feature = "async")]
+impl<T> From<tokio::sync::mpsc::error::SendError<T>> for Error {
+    fn from(_: tokio::sync::mpsc::error::SendError<T>) -> Self {
+        Self::Send
+    }
+}
+
 impl From<tungstenite::Error> for Error {
     fn from(error: tungstenite::Error) -> Self {
-        Self::Tungstenite(error)
+        Self::Tungstenite(Box::new(error))
     }
 }
 
@@ -77,8 +164,91 @@ impl From<Box<HandshakeError<ClientHandshake<MaybeTlsStream<TcpStream>>>>> for E
     }
 }
 
+#[cfg(feature = "async")]
+impl From<reqwest::Error> for Error {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Reqwest(Box::new(error))
+    }
+}
+
 impl From<ureq::Error> for Error {
     fn from(error: ureq::Error) -> Self {
-        Self::Ureq(error)
+        Self::Ureq(Box::new(error))
+    }
+}
+
+macro_rules! json_error_codes {
+    ($($name:ident = $code:literal,)*) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[non_exhaustive]
+        pub enum JsonErrorCode {
+            $($name,)*
+            Other(u32),
+        }
+
+        impl From<u32> for JsonErrorCode {
+            fn from(code: u32) -> Self {
+                match code {
+                    $($code => Self::$name,)*
+                    code => Self::Other(code),
+                }
+            }
+        }
+
+        impl From<JsonErrorCode> for u32 {
+            fn from(code: JsonErrorCode) -> Self {
+                match code {
+                    $(JsonErrorCode::$name => $code,)*
+                    JsonErrorCode::Other(code) => code,
+                }
+            }
+        }
+    };
+}
+
+json_error_codes! {
+    General = 0,
+    UnknownAccount = 10001,
+    UnknownApplication = 10002,
+    UnknownChannel = 10003,
+    UnknownGuild = 10004,
+    UnknownIntegration = 10005,
+    UnknownInvite = 10006,
+    UnknownMember = 10007,
+    UnknownMessage = 10008,
+    UnknownOverwrite = 10009,
+    UnknownRole = 10011,
+    UnknownToken = 10012,
+    UnknownUser = 10013,
+    UnknownEmoji = 10014,
+    UnknownWebhook = 10015,
+    UnknownBan = 10026,
+    UnknownInteraction = 10062,
+    BotsCannotUseEndpoint = 20001,
+    OnlyBotsCanUseEndpoint = 20002,
+    MaximumGuilds = 30001,
+    MaximumPinnedMessages = 30003,
+    MaximumRoles = 30005,
+    MaximumWebhooks = 30007,
+    MaximumReactions = 30010,
+    Unauthorized = 40001,
+    RequestEntityTooLarge = 40005,
+    MissingAccess = 50001,
+    InvalidAccountType = 50002,
+    CannotExecuteActionOnDmChannel = 50003,
+    CannotEditMessageByOtherUser = 50005,
+    CannotSendEmptyMessage = 50006,
+    CannotSendMessagesToUser = 50007,
+    CannotSendMessagesInVoiceChannel = 50008,
+    MissingPermissions = 50013,
+    InvalidToken = 50014,
+    InvalidWebhookToken = 50027,
+    MessageTooOldToBulkDelete = 50034,
+    InvalidFormBody = 50035,
+}
+
+impl<'de> Deserialize<'de> for JsonErrorCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u32::deserialize(deserializer).map(Into::into)
     }
 }
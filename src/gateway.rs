@@ -1,94 +1,169 @@
+#[cfg(feature = "async")]
+mod async_gateway;
+mod close_code;
 mod dispatch_event;
+mod encoding;
+pub mod etf;
 mod event;
+mod inflater;
 mod intents;
+pub(crate) mod payload;
+mod rate_limiter;
+mod sender;
+mod shard;
 mod status;
 
 use std::net::TcpStream as StdTcpStream;
-use std::ops::{Deref, DerefMut};
-use std::time::{Duration, SystemTime};
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use crossbeam_channel::{Receiver, Sender};
 use mio::net::TcpStream;
-use mio::{Interest, Poll, Token};
-use serde::{de, Deserialize};
-use serde_json::json;
+use mio::{Interest, Poll, Token, Waker};
+use serde::Deserialize;
+use serde_json::Value;
 use serde_repr::{Deserialize_repr, Serialize_repr};
+use tracing::{debug, trace, warn};
+use tungstenite::client::IntoClientRequest;
 use tungstenite::handshake::HandshakeError;
+use tungstenite::protocol::frame::coding::CloseCode as WsCloseCode;
+use tungstenite::protocol::CloseFrame;
 use tungstenite::stream::MaybeTlsStream;
 use tungstenite::WebSocket;
 
-use crate::consts::*;
-use crate::model::Activity;
-use crate::Result;
-pub use dispatch_event::{DispatchEvent, DispatchEventKind, Ready};
+use crate::http::Http;
+use crate::model::{Activity, SessionStartLimit};
+use crate::{Config, Error, Result};
+#[cfg(feature = "async")]
+pub use async_gateway::{AsyncGateway, AsyncGatewayReader, AsyncGatewayWriter};
+pub use close_code::CloseCode;
+pub use dispatch_event::{
+    ChannelPinsUpdate, ClientStatus, DispatchEvent, DispatchEventKind, GuildBan, GuildEmojisUpdate,
+    GuildIntegration, GuildIntegrationsUpdate, GuildMemberAdd, GuildMemberRemove,
+    GuildMemberUpdate, GuildMembersChunk, GuildRole, GuildRoleDelete, IntegrationDelete,
+    InviteCreate, InviteDelete, MessageDelete, MessageDeleteBulk, MessageReactionAdd,
+    MessageReactionRemove, MessageReactionRemoveAll, MessageReactionRemoveEmoji, MessageUpdate,
+    PresenceUpdate, PresenceUser, RawEvent, Ready, ThreadDelete, ThreadListSync,
+    ThreadMemberUpdate, ThreadMembersUpdate, TypingStart, VoiceServerUpdate, WebhooksUpdate,
+};
+pub use encoding::Encoding;
 pub use event::Event;
+use inflater::Inflater;
 pub use intents::Intents;
+use rate_limiter::RateLimiter;
+pub use sender::GatewaySender;
+pub use shard::Shard;
 pub use status::Status;
 
 #[derive(Debug)]
 pub struct Gateway {
-    socket: WebSocket<MaybeTlsStream<TcpStream>>,
+    socket: Socket,
     poll: Poll,
+    waker: Arc<Waker>,
+    commands: (Sender<Value>, Receiver<Value>),
+    queued: Option<Value>,
+    rate_limiter: RateLimiter,
+    config: Config,
     intents: Intents,
+    shard: Shard,
+    inflater: Option<Inflater>,
+    url: String,
+    session_start_limit: Option<SessionStartLimit>,
 }
 
 impl Deref for Gateway {
-    type Target = WebSocket<MaybeTlsStream<TcpStream>>;
+    type Target = Socket;
 
     fn deref(&self) -> &Self::Target {
         &self.socket
     }
 }
 
-impl DerefMut for Gateway {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.socket
+impl Gateway {
+    pub fn connect(token: &str, intents: Intents) -> Result<Self> {
+        Self::connect_with_config(Config::default(), token, intents)
     }
-}
 
-impl Gateway {
-    pub fn connect(intents: Intents) -> Result<Self> {
-        let url = {
-            let url = ureq::get(&api!("/gateway"))
-                .call()?
-                .into_json::<serde_json::Map<String, serde_json::Value>>()?
-                .remove("url")
-                .ok_or_else(|| de::Error::missing_field("url"))
-                .and_then(String::deserialize)?;
-            format!("{}/?v={}&encoding=json", url, API_VERSION)
-        };
-        let mut stream = {
-            let stream = StdTcpStream::connect((GATEWAY_HOSTNAME, GATEWAY_PORT))?;
-            stream.set_nonblocking(true)?;
-            TcpStream::from_std(stream)
+    pub fn connect_with_config(config: Config, token: &str, intents: Intents) -> Result<Self> {
+        let http = Http::with_config(token.into(), config.clone());
+        Self::connect_with_shard(config, &http, intents, Shard::default())
+    }
+
+    // `http` is only used to look up the gateway url when the config has none.
+    pub fn connect_with_shard(
+        config: Config,
+        http: &Http,
+        intents: Intents,
+        shard: Shard,
+    ) -> Result<Self> {
+        let (url, session_start_limit) = match &config.gateway_url {
+            Some(url) => (url.clone(), None),
+            None => {
+                let gateway_bot = http.get_gateway_bot()?;
+                (gateway_bot.url, Some(gateway_bot.session_start_limit))
+            }
         };
         let poll = Poll::new()?;
-        poll.registry()
-            .register(&mut stream, Token(0), Interest::READABLE)?;
-        let (socket, _) = match tungstenite::client_tls(url, stream) {
-            Ok(x) => x,
-            Err(HandshakeError::Interrupted(mut mid_handshake)) => loop {
-                match mid_handshake.handshake() {
-                    Ok(x) => break x,
-                    Err(HandshakeError::Interrupted(new_mid_handshake)) => {
-                        mid_handshake = new_mid_handshake;
-                    }
-                    Err(err) => return Err(err.into()),
-                }
-            },
-            Err(err) => return Err(err.into()),
-        };
+        let waker = Arc::new(Waker::new(poll.registry(), WAKER)?);
+        let socket = open(&config, &url, &poll)?;
         Ok(Self {
             socket,
             poll,
+            waker,
+            commands: crossbeam_channel::unbounded(),
+            queued: None,
+            rate_limiter: RateLimiter::new(),
+            inflater: config.compress.then(Inflater::new),
+            config,
             intents,
+            shard,
+            url,
+            session_start_limit,
         })
     }
 
+    pub fn shard(&self) -> Shard {
+        self.shard
+    }
+
+    pub fn sender(&self) -> GatewaySender {
+        GatewaySender {
+            commands: self.commands.0.clone(),
+            waker: self.waker.clone(),
+        }
+    }
+
+    // `None` when the gateway url comes from the config.
+    pub fn session_start_limit(&self) -> Option<&SessionStartLimit> {
+        self.session_start_limit.as_ref()
+    }
+
+    // Closes without a code, which keeps the session resumable.
     pub fn close(&mut self) -> Result {
-        self.socket.close(None)?;
+        self.close_with(None)
+    }
+
+    // Closes with code 1000, which ends the session.
+    pub fn shutdown(&mut self) -> Result {
+        self.close_with(Some(CloseFrame {
+            code: WsCloseCode::Normal,
+            reason: "".into(),
+        }))
+    }
+
+    // Waits for the close frame of the gateway, giving up after
+    // `CLOSE_TIMEOUT`.
+    fn close_with(&mut self, frame: Option<CloseFrame<'static>>) -> Result {
+        self.socket.close(frame)?;
+        let deadline = Instant::now() + CLOSE_TIMEOUT;
         let mut events = mio::Events::with_capacity(1);
         loop {
-            self.poll.poll(&mut events, None)?;
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            self.poll.poll(&mut events, Some(timeout))?;
+            if events.is_empty() {
+                return Ok(());
+            }
             loop {
                 match self.socket.read_message() {
                     Ok(_) => (),
@@ -105,34 +180,60 @@ impl Gateway {
     }
 
     pub fn reconnect(&mut self) -> Result {
-        println!("reconnect");
+        let url = self.url.clone();
+        self.reconnect_to(&url)
+    }
+
+    // Used to resume on the `resume_gateway_url` from `Ready`.
+    pub fn reconnect_to(&mut self, url: &str) -> Result {
+        debug!(url, "reconnecting");
         if let Err(err) = self.close() {
-            eprintln!("Gateway::close err: {:?}", err);
+            warn!(error = ?err, "failed to close the gateway connection");
         }
-        let mut gateway = Self::connect(self.intents)?;
-        std::mem::swap(self, &mut gateway);
+        self.socket = open(&self.config, url, &self.poll)?;
+        self.inflater = self.config.compress.then(Inflater::new);
+        self.rate_limiter = RateLimiter::new();
         Ok(())
     }
 
+    // Returns the events that are already available, without blocking.
     pub fn get_events(&mut self) -> Result<Vec<Event>> {
-        let mut events = mio::Events::with_capacity(1);
-        self.poll
-            .poll(&mut events, Some(Duration::from_millis(1)))?;
-        if events.is_empty() {
-            return Ok(vec![]);
+        self.wait_events(Some(Duration::ZERO))
+    }
+
+    // Writes the commands queued through `GatewaySender`s and blocks until
+    // events arrive, the timeout expires or a sender wakes the gateway up.
+    // Commands over the rate limit stay queued and are written by a later call.
+    pub fn wait_events(&mut self, timeout: Option<Duration>) -> Result<Vec<Event>> {
+        let delay = self.send_queued()?;
+        // The socket is edge-triggered, so whatever is already buffered (like
+        // frames that came along with the handshake response) has to be read
+        // before polling.
+        let events = self.read_events()?;
+        if !events.is_empty() {
+            return Ok(events);
+        }
+        let timeout = match (timeout, delay) {
+            (Some(timeout), Some(delay)) => Some(timeout.min(delay)),
+            (timeout, delay) => timeout.or(delay),
+        };
+        let mut ready = mio::Events::with_capacity(2);
+        match self.poll.poll(&mut ready, timeout) {
+            Ok(()) => (),
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => (),
+            Err(err) => return Err(err.into()),
         }
+        self.send_queued()?;
+        self.read_events()
+    }
+
+    fn read_events(&mut self) -> Result<Vec<Event>> {
         let mut events = Vec::new();
         loop {
             match self.socket.read_message() {
-                Ok(x) => {
-                    if let tungstenite::Message::Text(text) = x {
-                        println!("event: {:?}", text);
-                        if let Ok(event) = serde_json::from_str::<Event>(&text) {
-                            events.push(event);
-                        }
-                    } else {
-                        println!("message: {:?}", x);
-                    }
+                Ok(message) => {
+                    let event = decode(message, self.inflater.as_mut(), self.config.encoding)?;
+                    events.extend(event);
                 }
                 Err(tungstenite::Error::Io(err))
                     if err.kind() == std::io::ErrorKind::WouldBlock =>
@@ -144,34 +245,32 @@ impl Gateway {
         }
     }
 
+    // Returns how long the next queued command has to wait, if there is one.
+    fn send_queued(&mut self) -> Result<Option<Duration>> {
+        loop {
+            let payload = match self.queued.take() {
+                Some(payload) => payload,
+                None => match self.commands.1.try_recv() {
+                    Ok(payload) => payload,
+                    Err(_) => return Ok(None),
+                },
+            };
+            if let Some(delay) = self.rate_limiter.delay(false) {
+                self.queued = Some(payload);
+                return Ok(Some(delay));
+            }
+            self.send_command(payload)?;
+        }
+    }
+
     #[inline]
     pub fn heartbeat(&mut self, sequence_number: Option<u64>) -> Result {
-        let map = json!({
-            "op": OpCode::Heartbeat,
-            "d": sequence_number,
-        });
-        let message = tungstenite::Message::Text(serde_json::to_string(&map).unwrap());
-        self.socket.write_message(message)?;
-        Ok(())
+        self.send(payload::heartbeat(sequence_number))
     }
 
     #[inline]
     pub fn identify(&mut self, token: &str) -> Result {
-        let map = json!({
-            "op": OpCode::Identify,
-            "d": {
-                "token": token,
-                "properties": {
-                    "$os": std::env::consts::OS,
-                    "$browser": "harmony",
-                    "$device": "harmony",
-                },
-                "intents": self.intents,
-            }
-        });
-        let message = tungstenite::Message::Text(serde_json::to_string(&map).unwrap());
-        self.socket.write_message(message)?;
-        Ok(())
+        self.send(payload::identify(token, self.intents, self.shard))
     }
 
     #[inline]
@@ -181,40 +280,150 @@ impl Gateway {
         session_id: &Option<String>,
         sequence_number: Option<u64>,
     ) -> Result {
-        let map = json!({
-            "op": OpCode::Resume,
-            "d": {
-                "token": token,
-                "session_id": session_id,
-                "seq": sequence_number
-            }
-        });
-        let message = tungstenite::Message::Text(serde_json::to_string(&map).unwrap());
-        self.socket.write_message(message)?;
-        Ok(())
+        self.send(payload::resume(token, session_id, sequence_number))
     }
 
     #[inline]
     pub fn presence_update(&mut self, status: Status, activity: Option<Activity>) -> Result<()> {
-        let now = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as u64;
-        let map = json!({
-            "op": OpCode::PresenceUpdate,
-            "d": {
-                "since": now,
-                "activities": activity.into_iter().collect::<Vec<_>>(),
-                "status": status,
-                "afk": false
-            }
-        });
-        let message = tungstenite::Message::Text(serde_json::to_string(&map).unwrap());
+        self.send_command(payload::presence_update(status, activity))
+    }
+
+    fn send(&mut self, payload: Value) -> Result {
+        self.write(payload, true)
+    }
+
+    fn send_command(&mut self, payload: Value) -> Result {
+        self.write(payload, false)
+    }
+
+    fn write(&mut self, payload: Value, reserved: bool) -> Result {
+        let message = encode_limited(
+            &mut self.rate_limiter,
+            &payload,
+            reserved,
+            self.config.encoding,
+        )?;
         self.socket.write_message(message)?;
         Ok(())
     }
 }
 
+type Socket = WebSocket<MaybeTlsStream<TcpStream>>;
+
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+const SOCKET: Token = Token(0);
+const WAKER: Token = Token(1);
+
+fn open(config: &Config, url: &str, poll: &Poll) -> Result<Socket> {
+    let request = self::url(config, url).into_client_request()?;
+    let mut stream = {
+        let host = request.uri().host().unwrap_or_default();
+        let port = request
+            .uri()
+            .port_u16()
+            .unwrap_or(if config.tls { 443 } else { 80 });
+        let stream = StdTcpStream::connect((host, port))?;
+        stream.set_nonblocking(true)?;
+        TcpStream::from_std(stream)
+    };
+    poll.registry()
+        .register(&mut stream, SOCKET, Interest::READABLE)?;
+    let (socket, _) = match tungstenite::client_tls(request, stream) {
+        Ok(x) => x,
+        Err(HandshakeError::Interrupted(mut mid_handshake)) => loop {
+            match mid_handshake.handshake() {
+                Ok(x) => break x,
+                Err(HandshakeError::Interrupted(new_mid_handshake)) => {
+                    mid_handshake = new_mid_handshake;
+                }
+                Err(err) => return Err(err.into()),
+            }
+        },
+        Err(err) => return Err(err.into()),
+    };
+    Ok(socket)
+}
+
+pub(crate) fn url(config: &Config, url: &str) -> String {
+    let host = url.split_once("://").map_or(url, |(_, host)| host);
+    let scheme = if config.tls { "wss" } else { "ws" };
+    let mut url = format!(
+        "{}://{}/?v={}&encoding={}",
+        scheme,
+        host,
+        config.api_version,
+        config.encoding.as_str()
+    );
+    if config.compress {
+        url.push_str("&compress=zlib-stream");
+    }
+    url
+}
+
+pub(crate) fn decode(
+    message: tungstenite::Message,
+    inflater: Option<&mut Inflater>,
+    encoding: Encoding,
+) -> Result<Option<Event>> {
+    let data = match (message, inflater) {
+        (tungstenite::Message::Text(text), _) => text.into_bytes(),
+        (tungstenite::Message::Binary(data), Some(inflater)) => match inflater.inflate(&data)? {
+            Some(data) => data,
+            None => return Ok(None),
+        },
+        (tungstenite::Message::Binary(data), None) => data,
+        (tungstenite::Message::Close(Some(frame)), _) => {
+            return Err(Error::GatewayClosed {
+                code: u16::from(frame.code).into(),
+                reason: frame.reason.into_owned(),
+            });
+        }
+        (message, _) => {
+            trace!(?message, "received gateway message");
+            return Ok(None);
+        }
+    };
+    let event = match encoding {
+        Encoding::Json => {
+            trace!(payload = %String::from_utf8_lossy(&data), "received gateway payload");
+            serde_json::from_slice::<Event>(&data).map_err(Error::from)
+        }
+        Encoding::Etf => etf::decode(&data).and_then(|value| {
+            trace!(payload = %value, "received gateway payload");
+            Event::deserialize(value).map_err(Error::from)
+        }),
+    };
+    match event {
+        Ok(event) => Ok(Some(event)),
+        Err(err) => {
+            warn!(error = %err, "failed to decode gateway payload");
+            Ok(None)
+        }
+    }
+}
+
+// Takes a slot of the rate limit before encoding `payload`. Heartbeats,
+// identifies and resumes are `reserved` and may use the slots kept for them.
+pub(crate) fn encode_limited(
+    rate_limiter: &mut RateLimiter,
+    payload: &Value,
+    reserved: bool,
+    encoding: Encoding,
+) -> Result<tungstenite::Message> {
+    rate_limiter
+        .acquire(reserved)
+        .map_err(|retry_after| Error::GatewayRateLimited { retry_after })?;
+    encode(payload, encoding)
+}
+
+pub(crate) fn encode(payload: &Value, encoding: Encoding) -> Result<tungstenite::Message> {
+    trace!(payload = %payload::redacted(payload), "sending gateway payload");
+    Ok(match encoding {
+        Encoding::Json => tungstenite::Message::Text(serde_json::to_string(payload)?),
+        Encoding::Etf => tungstenite::Message::Binary(etf::encode(payload)),
+    })
+}
+
 #[derive(Debug, Serialize_repr, Deserialize_repr)]
 #[repr(u8)]
 #[non_exhaustive]
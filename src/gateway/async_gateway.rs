@@ -0,0 +1,194 @@
+use std::time::Duration;
+
+use futures_util::stream::{SplitSink, SplitStream};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::Value;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tungstenite::Message;
+
+use super::{
+    decode, encode_limited, payload, Encoding, Event, Inflater, Intents, RateLimiter, Shard, Status,
+};
+use crate::http::AsyncHttp;
+use crate::model::{Activity, SessionStartLimit};
+use crate::{Config, Result};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+#[derive(Debug)]
+pub struct AsyncGateway {
+    writer: AsyncGatewayWriter,
+    reader: AsyncGatewayReader,
+    intents: Intents,
+    shard: Shard,
+    session_start_limit: Option<SessionStartLimit>,
+}
+
+impl AsyncGateway {
+    pub async fn connect(token: &str, intents: Intents) -> Result<Self> {
+        Self::connect_with_config(Config::default(), token, intents).await
+    }
+
+    pub async fn connect_with_config(
+        config: Config,
+        token: &str,
+        intents: Intents,
+    ) -> Result<Self> {
+        let http = AsyncHttp::with_config(token.into(), config.clone());
+        Self::connect_with_shard(config, &http, intents, Shard::default()).await
+    }
+
+    // `http` is only used to look up the gateway url when the config has none.
+    pub async fn connect_with_shard(
+        config: Config,
+        http: &AsyncHttp,
+        intents: Intents,
+        shard: Shard,
+    ) -> Result<Self> {
+        let (url, session_start_limit) = match &config.gateway_url {
+            Some(url) => (url.clone(), None),
+            None => {
+                let gateway_bot = http.get_gateway_bot().await?;
+                (gateway_bot.url, Some(gateway_bot.session_start_limit))
+            }
+        };
+        let (socket, _) = tokio_tungstenite::connect_async(super::url(&config, &url)).await?;
+        let (sink, stream) = socket.split();
+        Ok(Self {
+            writer: AsyncGatewayWriter {
+                sink,
+                encoding: config.encoding,
+                rate_limiter: RateLimiter::new(),
+            },
+            reader: AsyncGatewayReader {
+                stream,
+                inflater: config.compress.then(Inflater::new),
+                encoding: config.encoding,
+            },
+            intents,
+            shard,
+            session_start_limit,
+        })
+    }
+
+    pub fn shard(&self) -> Shard {
+        self.shard
+    }
+
+    // `None` when the gateway url comes from the config.
+    pub fn session_start_limit(&self) -> Option<&SessionStartLimit> {
+        self.session_start_limit.as_ref()
+    }
+
+    pub fn split(self) -> (AsyncGatewayWriter, AsyncGatewayReader) {
+        (self.writer, self.reader)
+    }
+
+    pub async fn close(&mut self) -> Result {
+        self.writer.close().await?;
+        while self.reader.next_event().await?.is_some() {}
+        Ok(())
+    }
+
+    pub async fn next_event(&mut self) -> Result<Option<Event>> {
+        self.reader.next_event().await
+    }
+
+    pub async fn heartbeat(&mut self, sequence_number: Option<u64>) -> Result {
+        self.writer.heartbeat(sequence_number).await
+    }
+
+    pub async fn identify(&mut self, token: &str) -> Result {
+        self.writer.identify(token, self.intents, self.shard).await
+    }
+
+    pub async fn resume(
+        &mut self,
+        token: &str,
+        session_id: &Option<String>,
+        sequence_number: Option<u64>,
+    ) -> Result {
+        self.writer.resume(token, session_id, sequence_number).await
+    }
+
+    pub async fn presence_update(&mut self, status: Status, activity: Option<Activity>) -> Result {
+        self.writer.presence_update(status, activity).await
+    }
+}
+
+#[derive(Debug)]
+pub struct AsyncGatewayWriter {
+    sink: SplitSink<Socket, Message>,
+    encoding: Encoding,
+    rate_limiter: RateLimiter,
+}
+
+impl AsyncGatewayWriter {
+    pub async fn close(&mut self) -> Result {
+        self.sink.send(Message::Close(None)).await?;
+        Ok(())
+    }
+
+    pub async fn heartbeat(&mut self, sequence_number: Option<u64>) -> Result {
+        self.send(payload::heartbeat(sequence_number)).await
+    }
+
+    pub async fn identify(&mut self, token: &str, intents: Intents, shard: Shard) -> Result {
+        self.send(payload::identify(token, intents, shard)).await
+    }
+
+    pub async fn resume(
+        &mut self,
+        token: &str,
+        session_id: &Option<String>,
+        sequence_number: Option<u64>,
+    ) -> Result {
+        self.send(payload::resume(token, session_id, sequence_number))
+            .await
+    }
+
+    pub async fn presence_update(&mut self, status: Status, activity: Option<Activity>) -> Result {
+        self.send_command(payload::presence_update(status, activity))
+            .await
+    }
+
+    // How long a command has to wait for the rate limit, `None` if it can be
+    // sent right away.
+    pub(crate) fn command_delay(&mut self) -> Option<Duration> {
+        self.rate_limiter.delay(false)
+    }
+
+    pub(crate) async fn send(&mut self, payload: Value) -> Result {
+        self.write(payload, true).await
+    }
+
+    pub(crate) async fn send_command(&mut self, payload: Value) -> Result {
+        self.write(payload, false).await
+    }
+
+    async fn write(&mut self, payload: Value, reserved: bool) -> Result {
+        let message = encode_limited(&mut self.rate_limiter, &payload, reserved, self.encoding)?;
+        self.sink.send(message).await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct AsyncGatewayReader {
+    stream: SplitStream<Socket>,
+    inflater: Option<Inflater>,
+    encoding: Encoding,
+}
+
+impl AsyncGatewayReader {
+    // Returns `None` once the connection is closed.
+    pub async fn next_event(&mut self) -> Result<Option<Event>> {
+        while let Some(message) = self.stream.next().await {
+            if let Some(event) = decode(message?, self.inflater.as_mut(), self.encoding)? {
+                return Ok(Some(event));
+            }
+        }
+        Ok(None)
+    }
+}
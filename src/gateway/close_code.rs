@@ -0,0 +1,69 @@
+macro_rules! close_codes {
+    ($($name:ident = $code:literal,)*) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[non_exhaustive]
+        pub enum CloseCode {
+            $($name,)*
+            Other(u16),
+        }
+
+        impl From<u16> for CloseCode {
+            fn from(code: u16) -> Self {
+                match code {
+                    $($code => Self::$name,)*
+                    code => Self::Other(code),
+                }
+            }
+        }
+
+        impl From<CloseCode> for u16 {
+            fn from(code: CloseCode) -> Self {
+                match code {
+                    $(CloseCode::$name => $code,)*
+                    CloseCode::Other(code) => code,
+                }
+            }
+        }
+    };
+}
+
+close_codes! {
+    UnknownError = 4000,
+    UnknownOpcode = 4001,
+    DecodeError = 4002,
+    NotAuthenticated = 4003,
+    AuthenticationFailed = 4004,
+    AlreadyAuthenticated = 4005,
+    InvalidSequence = 4007,
+    RateLimited = 4008,
+    SessionTimedOut = 4009,
+    InvalidShard = 4010,
+    ShardingRequired = 4011,
+    InvalidApiVersion = 4012,
+    InvalidIntents = 4013,
+    DisallowedIntents = 4014,
+}
+
+impl CloseCode {
+    // The other codes come from the token, intents or shard settings, which a
+    // new connection would send again unchanged.
+    pub const fn can_reconnect(self) -> bool {
+        !matches!(
+            self,
+            Self::AuthenticationFailed
+                | Self::InvalidShard
+                | Self::ShardingRequired
+                | Self::InvalidApiVersion
+                | Self::InvalidIntents
+                | Self::DisallowedIntents
+        )
+    }
+
+    pub const fn can_resume(self) -> bool {
+        self.can_reconnect()
+            && !matches!(
+                self,
+                Self::NotAuthenticated | Self::InvalidSequence | Self::SessionTimedOut
+            )
+    }
+}
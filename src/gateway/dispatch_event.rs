@@ -1,7 +1,35 @@
 use serde::de;
 use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use tracing::warn;
 
-use crate::model::{Message, User};
+mod channel;
+mod guild;
+mod integration;
+mod invite;
+mod message;
+mod presence;
+mod voice;
+
+pub use channel::{
+    ChannelPinsUpdate, ThreadDelete, ThreadListSync, ThreadMemberUpdate, ThreadMembersUpdate,
+    WebhooksUpdate,
+};
+pub use guild::{
+    GuildBan, GuildEmojisUpdate, GuildIntegrationsUpdate, GuildMemberAdd, GuildMemberRemove,
+    GuildMemberUpdate, GuildMembersChunk, GuildRole, GuildRoleDelete,
+};
+pub use integration::{GuildIntegration, IntegrationDelete};
+pub use invite::{InviteCreate, InviteDelete};
+pub use message::{
+    MessageDelete, MessageDeleteBulk, MessageReactionAdd, MessageReactionRemove,
+    MessageReactionRemoveAll, MessageReactionRemoveEmoji, MessageUpdate,
+};
+pub use presence::{ClientStatus, PresenceUpdate, PresenceUser, TypingStart};
+pub use voice::VoiceServerUpdate;
+
+use crate::model::id::{ChannelId, GuildId};
+use crate::model::{Channel, Guild, Interaction, Message, UnavailableGuild, User, VoiceState};
 
 #[derive(Debug)]
 pub struct DispatchEvent {
@@ -25,12 +53,19 @@ impl<'de> Deserialize<'de> for DispatchEvent {
         let d = map
             .remove("d")
             .ok_or_else(|| de::Error::missing_field("d"))?;
-        let kind = match event_name.as_str() {
-            "READY" => Ready::deserialize(d).map(Into::into),
-            "MESSAGE_CREATE" => Message::deserialize(d).map(Into::into),
-            _ => Ok(DispatchEventKind::Unknown(d)),
+        let kind = typed_kind(&event_name, &d);
+        if let Some(Err(err)) = &kind {
+            warn!(error = %err, event = event_name, "failed to decode dispatch event");
         }
-        .map_err(de::Error::custom)?;
+        // Events that are not modelled, or that do not match their model, are
+        // passed on as is.
+        let kind = match kind {
+            Some(Ok(kind)) => kind,
+            _ => DispatchEventKind::Unknown(RawEvent {
+                name: event_name,
+                data: d,
+            }),
+        };
         Ok(Self {
             sequence_number,
             kind,
@@ -38,11 +73,146 @@ impl<'de> Deserialize<'de> for DispatchEvent {
     }
 }
 
+// `None` for events that are not modelled.
+fn typed_kind(name: &str, d: &Value) -> Option<serde_json::Result<DispatchEventKind>> {
+    Some(match name {
+        "READY" => Deserialize::deserialize(d).map(DispatchEventKind::Ready),
+        "RESUMED" => Ok(DispatchEventKind::Resumed),
+        "CHANNEL_CREATE" => Deserialize::deserialize(d).map(DispatchEventKind::ChannelCreate),
+        "CHANNEL_UPDATE" => Deserialize::deserialize(d).map(DispatchEventKind::ChannelUpdate),
+        "CHANNEL_DELETE" => Deserialize::deserialize(d).map(DispatchEventKind::ChannelDelete),
+        "CHANNEL_PINS_UPDATE" => {
+            Deserialize::deserialize(d).map(DispatchEventKind::ChannelPinsUpdate)
+        }
+        "THREAD_CREATE" => Deserialize::deserialize(d).map(DispatchEventKind::ThreadCreate),
+        "THREAD_UPDATE" => Deserialize::deserialize(d).map(DispatchEventKind::ThreadUpdate),
+        "THREAD_DELETE" => Deserialize::deserialize(d).map(DispatchEventKind::ThreadDelete),
+        "THREAD_LIST_SYNC" => Deserialize::deserialize(d).map(DispatchEventKind::ThreadListSync),
+        "THREAD_MEMBER_UPDATE" => {
+            Deserialize::deserialize(d).map(DispatchEventKind::ThreadMemberUpdate)
+        }
+        "THREAD_MEMBERS_UPDATE" => {
+            Deserialize::deserialize(d).map(DispatchEventKind::ThreadMembersUpdate)
+        }
+        "GUILD_CREATE" => Deserialize::deserialize(d).map(DispatchEventKind::GuildCreate),
+        "GUILD_UPDATE" => Deserialize::deserialize(d).map(DispatchEventKind::GuildUpdate),
+        "GUILD_DELETE" => Deserialize::deserialize(d).map(DispatchEventKind::GuildDelete),
+        "GUILD_BAN_ADD" => Deserialize::deserialize(d).map(DispatchEventKind::GuildBanAdd),
+        "GUILD_BAN_REMOVE" => Deserialize::deserialize(d).map(DispatchEventKind::GuildBanRemove),
+        "GUILD_EMOJIS_UPDATE" => {
+            Deserialize::deserialize(d).map(DispatchEventKind::GuildEmojisUpdate)
+        }
+        "GUILD_INTEGRATIONS_UPDATE" => {
+            Deserialize::deserialize(d).map(DispatchEventKind::GuildIntegrationsUpdate)
+        }
+        "GUILD_MEMBER_ADD" => Deserialize::deserialize(d).map(DispatchEventKind::GuildMemberAdd),
+        "GUILD_MEMBER_REMOVE" => {
+            Deserialize::deserialize(d).map(DispatchEventKind::GuildMemberRemove)
+        }
+        "GUILD_MEMBER_UPDATE" => {
+            Deserialize::deserialize(d).map(DispatchEventKind::GuildMemberUpdate)
+        }
+        "GUILD_MEMBERS_CHUNK" => {
+            Deserialize::deserialize(d).map(DispatchEventKind::GuildMembersChunk)
+        }
+        "GUILD_ROLE_CREATE" => Deserialize::deserialize(d).map(DispatchEventKind::GuildRoleCreate),
+        "GUILD_ROLE_UPDATE" => Deserialize::deserialize(d).map(DispatchEventKind::GuildRoleUpdate),
+        "GUILD_ROLE_DELETE" => Deserialize::deserialize(d).map(DispatchEventKind::GuildRoleDelete),
+        "INTEGRATION_CREATE" => {
+            Deserialize::deserialize(d).map(DispatchEventKind::IntegrationCreate)
+        }
+        "INTEGRATION_UPDATE" => {
+            Deserialize::deserialize(d).map(DispatchEventKind::IntegrationUpdate)
+        }
+        "INTEGRATION_DELETE" => {
+            Deserialize::deserialize(d).map(DispatchEventKind::IntegrationDelete)
+        }
+        "INTERACTION_CREATE" => {
+            Deserialize::deserialize(d).map(DispatchEventKind::InteractionCreate)
+        }
+        "INVITE_CREATE" => Deserialize::deserialize(d).map(DispatchEventKind::InviteCreate),
+        "INVITE_DELETE" => Deserialize::deserialize(d).map(DispatchEventKind::InviteDelete),
+        "MESSAGE_CREATE" => Deserialize::deserialize(d).map(DispatchEventKind::MessageCreate),
+        "MESSAGE_UPDATE" => Deserialize::deserialize(d).map(DispatchEventKind::MessageUpdate),
+        "MESSAGE_DELETE" => Deserialize::deserialize(d).map(DispatchEventKind::MessageDelete),
+        "MESSAGE_DELETE_BULK" => {
+            Deserialize::deserialize(d).map(DispatchEventKind::MessageDeleteBulk)
+        }
+        "MESSAGE_REACTION_ADD" => {
+            Deserialize::deserialize(d).map(DispatchEventKind::MessageReactionAdd)
+        }
+        "MESSAGE_REACTION_REMOVE" => {
+            Deserialize::deserialize(d).map(DispatchEventKind::MessageReactionRemove)
+        }
+        "MESSAGE_REACTION_REMOVE_ALL" => {
+            Deserialize::deserialize(d).map(DispatchEventKind::MessageReactionRemoveAll)
+        }
+        "MESSAGE_REACTION_REMOVE_EMOJI" => {
+            Deserialize::deserialize(d).map(DispatchEventKind::MessageReactionRemoveEmoji)
+        }
+        "PRESENCE_UPDATE" => Deserialize::deserialize(d).map(DispatchEventKind::PresenceUpdate),
+        "TYPING_START" => Deserialize::deserialize(d).map(DispatchEventKind::TypingStart),
+        "USER_UPDATE" => Deserialize::deserialize(d).map(DispatchEventKind::UserUpdate),
+        "VOICE_STATE_UPDATE" => {
+            Deserialize::deserialize(d).map(DispatchEventKind::VoiceStateUpdate)
+        }
+        "VOICE_SERVER_UPDATE" => {
+            Deserialize::deserialize(d).map(DispatchEventKind::VoiceServerUpdate)
+        }
+        "WEBHOOKS_UPDATE" => Deserialize::deserialize(d).map(DispatchEventKind::WebhooksUpdate),
+        _ => return None,
+    })
+}
+
 #[derive(Debug)]
 pub enum DispatchEventKind {
     Ready(Ready),
+    Resumed,
+    ChannelCreate(Box<Channel>),
+    ChannelUpdate(Box<Channel>),
+    ChannelDelete(Box<Channel>),
+    ChannelPinsUpdate(ChannelPinsUpdate),
+    ThreadCreate(Box<Channel>),
+    ThreadUpdate(Box<Channel>),
+    ThreadDelete(ThreadDelete),
+    ThreadListSync(ThreadListSync),
+    ThreadMemberUpdate(ThreadMemberUpdate),
+    ThreadMembersUpdate(ThreadMembersUpdate),
+    GuildCreate(Box<Guild>),
+    GuildUpdate(Box<Guild>),
+    GuildDelete(UnavailableGuild),
+    GuildBanAdd(GuildBan),
+    GuildBanRemove(GuildBan),
+    GuildEmojisUpdate(GuildEmojisUpdate),
+    GuildIntegrationsUpdate(GuildIntegrationsUpdate),
+    GuildMemberAdd(Box<GuildMemberAdd>),
+    GuildMemberRemove(GuildMemberRemove),
+    GuildMemberUpdate(Box<GuildMemberUpdate>),
+    GuildMembersChunk(GuildMembersChunk),
+    GuildRoleCreate(GuildRole),
+    GuildRoleUpdate(GuildRole),
+    GuildRoleDelete(GuildRoleDelete),
+    IntegrationCreate(Box<GuildIntegration>),
+    IntegrationUpdate(Box<GuildIntegration>),
+    IntegrationDelete(IntegrationDelete),
+    InteractionCreate(Box<Interaction>),
+    InviteCreate(Box<InviteCreate>),
+    InviteDelete(InviteDelete),
     MessageCreate(Box<Message>),
-    Unknown(serde_json::Value),
+    MessageUpdate(Box<MessageUpdate>),
+    MessageDelete(MessageDelete),
+    MessageDeleteBulk(MessageDeleteBulk),
+    MessageReactionAdd(Box<MessageReactionAdd>),
+    MessageReactionRemove(MessageReactionRemove),
+    MessageReactionRemoveAll(MessageReactionRemoveAll),
+    MessageReactionRemoveEmoji(MessageReactionRemoveEmoji),
+    PresenceUpdate(Box<PresenceUpdate>),
+    TypingStart(Box<TypingStart>),
+    UserUpdate(User),
+    VoiceStateUpdate(Box<VoiceState>),
+    VoiceServerUpdate(VoiceServerUpdate),
+    WebhooksUpdate(WebhooksUpdate),
+    Unknown(RawEvent),
 }
 
 impl DispatchEventKind {
@@ -60,6 +230,92 @@ impl DispatchEventKind {
         }
     }
 
+    pub fn guild_id(&self) -> Option<GuildId> {
+        match self {
+            Self::Ready(_) | Self::Resumed | Self::UserUpdate(_) => None,
+            Self::ChannelCreate(event) => event.guild_id,
+            Self::ChannelUpdate(event) => event.guild_id,
+            Self::ChannelDelete(event) => event.guild_id,
+            Self::ChannelPinsUpdate(event) => event.guild_id,
+            Self::ThreadCreate(event) => event.guild_id,
+            Self::ThreadUpdate(event) => event.guild_id,
+            Self::ThreadDelete(event) => Some(event.guild_id),
+            Self::ThreadListSync(event) => Some(event.guild_id),
+            Self::ThreadMemberUpdate(event) => Some(event.guild_id),
+            Self::ThreadMembersUpdate(event) => Some(event.guild_id),
+            Self::GuildCreate(event) => Some(event.id),
+            Self::GuildUpdate(event) => Some(event.id),
+            Self::GuildDelete(event) => Some(event.id),
+            Self::GuildBanAdd(event) => Some(event.guild_id),
+            Self::GuildBanRemove(event) => Some(event.guild_id),
+            Self::GuildEmojisUpdate(event) => Some(event.guild_id),
+            Self::GuildIntegrationsUpdate(event) => Some(event.guild_id),
+            Self::GuildMemberAdd(event) => Some(event.guild_id),
+            Self::GuildMemberRemove(event) => Some(event.guild_id),
+            Self::GuildMemberUpdate(event) => Some(event.guild_id),
+            Self::GuildMembersChunk(event) => Some(event.guild_id),
+            Self::GuildRoleCreate(event) => Some(event.guild_id),
+            Self::GuildRoleUpdate(event) => Some(event.guild_id),
+            Self::GuildRoleDelete(event) => Some(event.guild_id),
+            Self::IntegrationCreate(event) => Some(event.guild_id),
+            Self::IntegrationUpdate(event) => Some(event.guild_id),
+            Self::IntegrationDelete(event) => Some(event.guild_id),
+            Self::InteractionCreate(event) => event.guild_id,
+            Self::InviteCreate(event) => event.guild_id,
+            Self::InviteDelete(event) => event.guild_id,
+            Self::MessageCreate(event) => event.guild_id,
+            Self::MessageUpdate(event) => event.guild_id,
+            Self::MessageDelete(event) => event.guild_id,
+            Self::MessageDeleteBulk(event) => event.guild_id,
+            Self::MessageReactionAdd(event) => event.guild_id,
+            Self::MessageReactionRemove(event) => event.guild_id,
+            Self::MessageReactionRemoveAll(event) => event.guild_id,
+            Self::MessageReactionRemoveEmoji(event) => event.guild_id,
+            Self::PresenceUpdate(event) => Some(event.guild_id),
+            Self::TypingStart(event) => event.guild_id,
+            Self::VoiceStateUpdate(event) => event.guild_id,
+            Self::VoiceServerUpdate(event) => Some(event.guild_id),
+            Self::WebhooksUpdate(event) => Some(event.guild_id),
+            Self::Unknown(event) => event
+                .data
+                .get("guild_id")
+                .and_then(|guild_id| GuildId::deserialize(guild_id).ok()),
+        }
+    }
+
+    pub fn channel_id(&self) -> Option<ChannelId> {
+        match self {
+            Self::ChannelCreate(event)
+            | Self::ChannelUpdate(event)
+            | Self::ChannelDelete(event)
+            | Self::ThreadCreate(event)
+            | Self::ThreadUpdate(event) => Some(event.id),
+            Self::ChannelPinsUpdate(event) => Some(event.channel_id),
+            Self::ThreadDelete(event) => Some(event.id),
+            Self::ThreadMemberUpdate(event) => event.member.id,
+            Self::ThreadMembersUpdate(event) => Some(event.id),
+            Self::InteractionCreate(event) => event.channel_id,
+            Self::InviteCreate(event) => Some(event.channel_id),
+            Self::InviteDelete(event) => Some(event.channel_id),
+            Self::MessageCreate(event) => Some(event.channel_id),
+            Self::MessageUpdate(event) => Some(event.channel_id),
+            Self::MessageDelete(event) => Some(event.channel_id),
+            Self::MessageDeleteBulk(event) => Some(event.channel_id),
+            Self::MessageReactionAdd(event) => Some(event.channel_id),
+            Self::MessageReactionRemove(event) => Some(event.channel_id),
+            Self::MessageReactionRemoveAll(event) => Some(event.channel_id),
+            Self::MessageReactionRemoveEmoji(event) => Some(event.channel_id),
+            Self::TypingStart(event) => Some(event.channel_id),
+            Self::VoiceStateUpdate(event) => event.channel_id,
+            Self::WebhooksUpdate(event) => Some(event.channel_id),
+            Self::Unknown(event) => event
+                .data
+                .get("channel_id")
+                .and_then(|channel_id| ChannelId::deserialize(channel_id).ok()),
+            _ => None,
+        }
+    }
+
     pub const fn as_message(&self) -> Option<&Message> {
         match self {
             Self::MessageCreate(message) => Some(message),
@@ -68,13 +324,21 @@ impl DispatchEventKind {
     }
 }
 
+// An event that is not modelled yet, with its original payload.
+#[derive(Debug, Clone)]
+pub struct RawEvent {
+    pub name: String,
+    pub data: Value,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Ready {
     #[serde(rename = "v")]
     pub version: u64,
     pub user: User,
-    // pub guilds: Vec<UnavailableGuild>,
+    pub guilds: Vec<UnavailableGuild>,
     pub session_id: String,
+    pub resume_gateway_url: Option<String>,
     pub shard: Option<[u64; 2]>,
     // pub application: Application,
 }
@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::model::id::{ChannelId, GuildId, UserId};
+use crate::model::{Channel, ChannelKind, ThreadMember};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChannelPinsUpdate {
+    pub guild_id: Option<GuildId>,
+    pub channel_id: ChannelId,
+    pub last_pin_timestamp: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ThreadDelete {
+    pub id: ChannelId,
+    pub guild_id: GuildId,
+    pub parent_id: ChannelId,
+    #[serde(rename = "type")]
+    pub kind: ChannelKind,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ThreadListSync {
+    pub guild_id: GuildId,
+    // Missing when the threads of the whole guild are synced.
+    #[serde(default)]
+    pub channel_ids: Vec<ChannelId>,
+    pub threads: Vec<Channel>,
+    pub members: Vec<ThreadMember>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ThreadMemberUpdate {
+    pub guild_id: GuildId,
+    #[serde(flatten)]
+    pub member: ThreadMember,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ThreadMembersUpdate {
+    pub id: ChannelId,
+    pub guild_id: GuildId,
+    pub member_count: u64,
+    #[serde(default)]
+    pub added_members: Vec<ThreadMember>,
+    #[serde(default)]
+    pub removed_member_ids: Vec<UserId>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhooksUpdate {
+    pub guild_id: GuildId,
+    pub channel_id: ChannelId,
+}
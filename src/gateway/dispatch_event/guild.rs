@@ -0,0 +1,78 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::model::id::{GuildId, RoleId, UserId};
+use crate::model::{Emoji, Member, Role, User};
+
+// `GUILD_BAN_ADD` and `GUILD_BAN_REMOVE`
+#[derive(Debug, Clone, Deserialize)]
+pub struct GuildBan {
+    pub guild_id: GuildId,
+    pub user: User,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GuildEmojisUpdate {
+    pub guild_id: GuildId,
+    pub emojis: Vec<Emoji>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GuildIntegrationsUpdate {
+    pub guild_id: GuildId,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GuildMemberAdd {
+    pub guild_id: GuildId,
+    #[serde(flatten)]
+    pub member: Member,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GuildMemberRemove {
+    pub guild_id: GuildId,
+    pub user: User,
+}
+
+// Unlike `Member`, `joined_at`, `deaf` and `mute` may be missing.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GuildMemberUpdate {
+    pub guild_id: GuildId,
+    pub roles: Vec<RoleId>,
+    pub user: User,
+    pub nick: Option<String>,
+    pub avatar: Option<String>,
+    pub joined_at: Option<DateTime<Utc>>,
+    pub premium_since: Option<DateTime<Utc>>,
+    pub deaf: Option<bool>,
+    pub mute: Option<bool>,
+    #[serde(default)]
+    pub pending: bool,
+    pub communication_disabled_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GuildMembersChunk {
+    pub guild_id: GuildId,
+    pub members: Vec<Member>,
+    pub chunk_index: u64,
+    pub chunk_count: u64,
+    #[serde(default)]
+    pub not_found: Vec<UserId>,
+    // pub presences: Vec<PresenceUpdate>,
+    pub nonce: Option<String>,
+}
+
+// `GUILD_ROLE_CREATE` and `GUILD_ROLE_UPDATE`
+#[derive(Debug, Clone, Deserialize)]
+pub struct GuildRole {
+    pub guild_id: GuildId,
+    pub role: Role,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GuildRoleDelete {
+    pub guild_id: GuildId,
+    pub role_id: RoleId,
+}
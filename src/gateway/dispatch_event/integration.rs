@@ -0,0 +1,19 @@
+use serde::Deserialize;
+
+use crate::model::id::{ApplicationId, GuildId, IntegrationId};
+use crate::model::Integration;
+
+// `INTEGRATION_CREATE` and `INTEGRATION_UPDATE`
+#[derive(Debug, Clone, Deserialize)]
+pub struct GuildIntegration {
+    pub guild_id: GuildId,
+    #[serde(flatten)]
+    pub integration: Integration,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IntegrationDelete {
+    pub id: IntegrationId,
+    pub guild_id: GuildId,
+    pub application_id: Option<ApplicationId>,
+}
@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::model::id::{ChannelId, GuildId};
+use crate::model::User;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InviteCreate {
+    pub channel_id: ChannelId,
+    pub code: String,
+    pub created_at: DateTime<Utc>,
+    pub guild_id: Option<GuildId>,
+    pub inviter: Option<User>,
+    pub max_age: u64,
+    pub max_uses: u64,
+    pub target_type: Option<u64>,
+    pub target_user: Option<User>,
+    // pub target_application: Option<Application>,
+    pub temporary: bool,
+    pub uses: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InviteDelete {
+    pub channel_id: ChannelId,
+    pub guild_id: Option<GuildId>,
+    pub code: String,
+}
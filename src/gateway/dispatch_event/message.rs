@@ -0,0 +1,72 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::model::id::{ChannelId, GuildId, MessageId, RoleId, UserId};
+use crate::model::{Emoji, Member, PartialMember, User};
+
+// Only `id` and `channel_id` are guaranteed, the other fields are sent when
+// they changed.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageUpdate {
+    pub id: MessageId,
+    pub channel_id: ChannelId,
+    pub guild_id: Option<GuildId>,
+    pub author: Option<User>,
+    pub member: Option<PartialMember>,
+    pub content: Option<String>,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub edited_timestamp: Option<DateTime<Utc>>,
+    pub tts: Option<bool>,
+    pub mention_everyone: Option<bool>,
+    pub mentions: Option<Vec<User>>,
+    pub mention_roles: Option<Vec<RoleId>>,
+    pub pinned: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageDelete {
+    pub id: MessageId,
+    pub channel_id: ChannelId,
+    pub guild_id: Option<GuildId>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageDeleteBulk {
+    pub ids: Vec<MessageId>,
+    pub channel_id: ChannelId,
+    pub guild_id: Option<GuildId>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageReactionAdd {
+    pub user_id: UserId,
+    pub channel_id: ChannelId,
+    pub message_id: MessageId,
+    pub guild_id: Option<GuildId>,
+    pub member: Option<Member>,
+    pub emoji: Emoji,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageReactionRemove {
+    pub user_id: UserId,
+    pub channel_id: ChannelId,
+    pub message_id: MessageId,
+    pub guild_id: Option<GuildId>,
+    pub emoji: Emoji,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageReactionRemoveAll {
+    pub channel_id: ChannelId,
+    pub message_id: MessageId,
+    pub guild_id: Option<GuildId>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageReactionRemoveEmoji {
+    pub channel_id: ChannelId,
+    pub guild_id: Option<GuildId>,
+    pub message_id: MessageId,
+    pub emoji: Emoji,
+}
@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::gateway::Status;
+use crate::model::id::{ChannelId, GuildId, UserId};
+use crate::model::{Activity, Member};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PresenceUpdate {
+    pub user: PresenceUser,
+    pub guild_id: GuildId,
+    pub status: Status,
+    pub activities: Vec<Activity>,
+    pub client_status: ClientStatus,
+}
+
+// Only `id` is guaranteed, the rest of the user is sent when it changed.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PresenceUser {
+    pub id: UserId,
+    pub username: Option<String>,
+    pub discriminator: Option<String>,
+    pub avatar: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClientStatus {
+    pub desktop: Option<Status>,
+    pub mobile: Option<Status>,
+    pub web: Option<Status>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TypingStart {
+    pub channel_id: ChannelId,
+    pub guild_id: Option<GuildId>,
+    pub user_id: UserId,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub timestamp: DateTime<Utc>,
+    pub member: Option<Member>,
+}
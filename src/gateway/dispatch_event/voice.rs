@@ -0,0 +1,11 @@
+use serde::Deserialize;
+
+use crate::model::id::GuildId;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct VoiceServerUpdate {
+    pub token: String,
+    pub guild_id: GuildId,
+    // `None` while the voice server is being reallocated.
+    pub endpoint: Option<String>,
+}
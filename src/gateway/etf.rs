@@ -0,0 +1,292 @@
+use std::io::{self, Read};
+
+use flate2::read::ZlibDecoder;
+use serde_json::{Map, Number, Value};
+
+use crate::Result;
+
+const VERSION: u8 = 131;
+const COMPRESSED: u8 = 80;
+const NEW_FLOAT_EXT: u8 = 70;
+const SMALL_INTEGER_EXT: u8 = 97;
+const INTEGER_EXT: u8 = 98;
+const FLOAT_EXT: u8 = 99;
+const ATOM_EXT: u8 = 100;
+const SMALL_TUPLE_EXT: u8 = 104;
+const LARGE_TUPLE_EXT: u8 = 105;
+const NIL_EXT: u8 = 106;
+const STRING_EXT: u8 = 107;
+const LIST_EXT: u8 = 108;
+const BINARY_EXT: u8 = 109;
+const SMALL_BIG_EXT: u8 = 110;
+const LARGE_BIG_EXT: u8 = 111;
+const SMALL_ATOM_EXT: u8 = 115;
+const MAP_EXT: u8 = 116;
+const ATOM_UTF8_EXT: u8 = 118;
+const SMALL_ATOM_UTF8_EXT: u8 = 119;
+// Same as the recursion limit of serde_json.
+const MAX_DEPTH: usize = 128;
+
+// Terms are mapped to JSON values so that ETF payloads go through the same
+// deserializers as JSON ones: atoms `nil`, `true` and `false` become null and
+// booleans, other atoms and binaries become strings, tuples and lists become
+// arrays.
+pub fn decode(data: &[u8]) -> Result<Value> {
+    let mut decoder = Decoder { data, depth: 0 };
+    match decoder.u8()? {
+        VERSION => (),
+        version => return Err(invalid(format!("unsupported version {}", version))),
+    }
+    let value = match decoder.data.first() {
+        Some(&COMPRESSED) => {
+            decoder.u8()?;
+            // The uncompressed size comes from the wire, so it is not
+            // trusted for preallocating.
+            decoder.u32()?;
+            let mut data = Vec::new();
+            ZlibDecoder::new(decoder.data).read_to_end(&mut data)?;
+            decoder.data = &[];
+            Decoder {
+                data: &data,
+                depth: 0,
+            }
+            .term()?
+        }
+        _ => decoder.term()?,
+    };
+    if !decoder.data.is_empty() {
+        return Err(invalid("trailing bytes"));
+    }
+    Ok(value)
+}
+
+pub fn encode(value: &Value) -> Vec<u8> {
+    let mut data = vec![VERSION];
+    encode_term(&mut data, value);
+    data
+}
+
+struct Decoder<'a> {
+    data: &'a [u8],
+    depth: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.data.len() < len {
+            return Err(invalid("unexpected end of data"));
+        }
+        let (bytes, data) = self.data.split_at(len);
+        self.data = data;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn string(&mut self, len: usize) -> Result<String> {
+        let bytes = self.bytes(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|err| invalid(err.to_string()))
+    }
+
+    fn term(&mut self) -> Result<Value> {
+        if self.depth == MAX_DEPTH {
+            return Err(invalid("terms nested too deeply"));
+        }
+        self.depth += 1;
+        let value = self.tagged();
+        self.depth -= 1;
+        value
+    }
+
+    fn tagged(&mut self) -> Result<Value> {
+        match self.u8()? {
+            SMALL_INTEGER_EXT => Ok(self.u8()?.into()),
+            INTEGER_EXT => Ok((self.u32()? as i32).into()),
+            NEW_FLOAT_EXT => {
+                let bytes = self.bytes(8)?;
+                let float = f64::from_be_bytes(bytes.try_into().unwrap_or_default());
+                Ok(float.into())
+            }
+            FLOAT_EXT => {
+                let float = self.string(31)?;
+                float
+                    .trim_end_matches('\0')
+                    .parse::<f64>()
+                    .map(Value::from)
+                    .map_err(|err| invalid(err.to_string()))
+            }
+            ATOM_EXT | ATOM_UTF8_EXT => {
+                let len = self.u16()? as usize;
+                self.atom(len)
+            }
+            SMALL_ATOM_EXT | SMALL_ATOM_UTF8_EXT => {
+                let len = self.u8()? as usize;
+                self.atom(len)
+            }
+            SMALL_TUPLE_EXT => {
+                let arity = self.u8()? as usize;
+                Ok(self.terms(arity)?.into())
+            }
+            LARGE_TUPLE_EXT => {
+                let arity = self.u32()? as usize;
+                Ok(self.terms(arity)?.into())
+            }
+            NIL_EXT => Ok(Value::Array(Vec::new())),
+            STRING_EXT => {
+                let len = self.u16()? as usize;
+                Ok(self.string(len)?.into())
+            }
+            LIST_EXT => {
+                let len = self.u32()? as usize;
+                let mut array = self.terms(len)?;
+                match self.term()? {
+                    Value::Array(tail) if tail.is_empty() => (),
+                    tail => array.push(tail),
+                }
+                Ok(array.into())
+            }
+            BINARY_EXT => {
+                let len = self.u32()? as usize;
+                Ok(self.string(len)?.into())
+            }
+            SMALL_BIG_EXT => {
+                let len = self.u8()? as usize;
+                self.big(len)
+            }
+            LARGE_BIG_EXT => {
+                let len = self.u32()? as usize;
+                self.big(len)
+            }
+            MAP_EXT => {
+                let arity = self.u32()? as usize;
+                let mut map = Map::new();
+                for _ in 0..arity {
+                    let key = match self.term()? {
+                        Value::String(key) => key,
+                        Value::Number(key) => key.to_string(),
+                        key => return Err(invalid(format!("unsupported map key {}", key))),
+                    };
+                    let value = self.term()?;
+                    map.insert(key, value);
+                }
+                Ok(map.into())
+            }
+            tag => Err(invalid(format!("unsupported tag {}", tag))),
+        }
+    }
+
+    fn atom(&mut self, len: usize) -> Result<Value> {
+        let atom = self.string(len)?;
+        Ok(match atom.as_str() {
+            "nil" | "null" => Value::Null,
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            _ => atom.into(),
+        })
+    }
+
+    fn terms(&mut self, len: usize) -> Result<Vec<Value>> {
+        (0..len).map(|_| self.term()).collect()
+    }
+
+    fn big(&mut self, len: usize) -> Result<Value> {
+        let negative = self.u8()? != 0;
+        let digits = self.bytes(len)?;
+        if digits.iter().skip(8).any(|&digit| digit != 0) {
+            return Err(invalid("integer does not fit in 64 bits"));
+        }
+        let magnitude = digits
+            .iter()
+            .take(8)
+            .rev()
+            .fold(0u64, |acc, &digit| acc << 8 | digit as u64);
+        if !negative {
+            Ok(magnitude.into())
+        } else if magnitude <= i64::MAX as u64 + 1 {
+            Ok((magnitude as i64).wrapping_neg().into())
+        } else {
+            Err(invalid("integer does not fit in 64 bits"))
+        }
+    }
+}
+
+fn encode_term(data: &mut Vec<u8>, value: &Value) {
+    match value {
+        Value::Null => encode_atom(data, "nil"),
+        Value::Bool(true) => encode_atom(data, "true"),
+        Value::Bool(false) => encode_atom(data, "false"),
+        Value::Number(number) => encode_number(data, number),
+        Value::String(string) => encode_binary(data, string),
+        Value::Array(array) if array.is_empty() => data.push(NIL_EXT),
+        Value::Array(array) => {
+            data.push(LIST_EXT);
+            data.extend_from_slice(&(array.len() as u32).to_be_bytes());
+            for value in array {
+                encode_term(data, value);
+            }
+            data.push(NIL_EXT);
+        }
+        Value::Object(map) => {
+            data.push(MAP_EXT);
+            data.extend_from_slice(&(map.len() as u32).to_be_bytes());
+            for (key, value) in map {
+                encode_binary(data, key);
+                encode_term(data, value);
+            }
+        }
+    }
+}
+
+fn encode_atom(data: &mut Vec<u8>, atom: &str) {
+    data.push(SMALL_ATOM_UTF8_EXT);
+    data.push(atom.len() as u8);
+    data.extend_from_slice(atom.as_bytes());
+}
+
+fn encode_binary(data: &mut Vec<u8>, binary: &str) {
+    data.push(BINARY_EXT);
+    data.extend_from_slice(&(binary.len() as u32).to_be_bytes());
+    data.extend_from_slice(binary.as_bytes());
+}
+
+fn encode_number(data: &mut Vec<u8>, number: &Number) {
+    if let Some(integer) = number.as_u64().and_then(|x| u8::try_from(x).ok()) {
+        data.push(SMALL_INTEGER_EXT);
+        data.push(integer);
+    } else if let Some(integer) = number.as_i64().and_then(|x| i32::try_from(x).ok()) {
+        data.push(INTEGER_EXT);
+        data.extend_from_slice(&integer.to_be_bytes());
+    } else if let Some(integer) = number.as_u64() {
+        encode_big(data, false, integer);
+    } else if let Some(integer) = number.as_i64() {
+        encode_big(data, true, integer.unsigned_abs());
+    } else {
+        data.push(NEW_FLOAT_EXT);
+        data.extend_from_slice(&number.as_f64().unwrap_or_default().to_be_bytes());
+    }
+}
+
+fn encode_big(data: &mut Vec<u8>, negative: bool, magnitude: u64) {
+    let digits = magnitude.to_le_bytes();
+    let len = 8 - magnitude.leading_zeros() as usize / 8;
+    data.push(SMALL_BIG_EXT);
+    data.push(len as u8);
+    data.push(negative as u8);
+    data.extend_from_slice(&digits[..len]);
+}
+
+fn invalid<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> crate::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error).into()
+}
@@ -0,0 +1,99 @@
+use std::io;
+
+use flate2::{Decompress, FlushDecompress};
+
+use crate::Result;
+
+const ZLIB_SUFFIX: [u8; 4] = [0x00, 0x00, 0xff, 0xff];
+
+// Inflate context for `compress=zlib-stream`, shared by every frame of a
+// connection. A payload may span several frames and is only complete once the
+// `Z_SYNC_FLUSH` suffix has been received.
+#[derive(Debug)]
+pub(crate) struct Inflater {
+    decompress: Decompress,
+    buffer: Vec<u8>,
+}
+
+impl Inflater {
+    pub fn new() -> Self {
+        Self {
+            decompress: Decompress::new(true),
+            buffer: Vec::new(),
+        }
+    }
+
+    pub fn inflate(&mut self, data: &[u8]) -> Result<Option<Vec<u8>>> {
+        self.buffer.extend_from_slice(data);
+        if !self.buffer.ends_with(&ZLIB_SUFFIX) {
+            return Ok(None);
+        }
+        let mut output = Vec::with_capacity(self.buffer.len() * 4);
+        let mut input = &self.buffer[..];
+        loop {
+            let total_in = self.decompress.total_in();
+            let total_out = self.decompress.total_out();
+            self.decompress
+                .decompress_vec(input, &mut output, FlushDecompress::Sync)
+                .map_err(io::Error::from)?;
+            input = &input[(self.decompress.total_in() - total_in) as usize..];
+            if input.is_empty() && output.len() < output.capacity() {
+                break;
+            }
+            // E.g. after the end of the stream, where no input is taken anymore.
+            if self.decompress.total_in() == total_in
+                && self.decompress.total_out() == total_out
+                && output.len() < output.capacity()
+            {
+                self.buffer.clear();
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "zlib stream made no progress",
+                )
+                .into());
+            }
+            output.reserve(output.capacity());
+        }
+        self.buffer.clear();
+        Ok(Some(output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use flate2::{Compress, Compression, FlushCompress};
+
+    use super::*;
+    use crate::Error;
+
+    fn deflate(compress: &mut Compress, data: &[u8], flush: FlushCompress) -> Vec<u8> {
+        let mut output = Vec::with_capacity(data.len() + 64);
+        compress.compress_vec(data, &mut output, flush).unwrap();
+        output
+    }
+
+    #[test]
+    fn split_frames() {
+        let mut compress = Compress::new(Compression::default(), true);
+        let mut inflater = Inflater::new();
+        let first = deflate(&mut compress, b"{\"op\":10}", FlushCompress::Sync);
+        let (head, tail) = first.split_at(first.len() / 2);
+        assert_eq!(inflater.inflate(head).unwrap(), None);
+        assert_eq!(inflater.inflate(tail).unwrap().unwrap(), b"{\"op\":10}");
+        // The context carries over to the next payload.
+        let second = deflate(&mut compress, b"{\"op\":11}", FlushCompress::Sync);
+        assert_eq!(inflater.inflate(&second).unwrap().unwrap(), b"{\"op\":11}");
+    }
+
+    #[test]
+    fn data_after_stream_end() {
+        let mut compress = Compress::new(Compression::default(), true);
+        let mut data = deflate(&mut compress, b"{\"op\":11}", FlushCompress::Finish);
+        data.extend_from_slice(&[0x01, 0x02]);
+        data.extend_from_slice(&ZLIB_SUFFIX);
+        match Inflater::new().inflate(&data) {
+            Err(Error::Io(err)) => assert_eq!(err.kind(), io::ErrorKind::InvalidData),
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use std::time::SystemTime;
+
+use serde_json::{json, Value};
+
+use super::{Intents, OpCode, Shard, Status};
+use crate::model::Activity;
+
+// Copy of an outgoing payload that is safe to log.
+pub(crate) fn redacted(payload: &Value) -> Value {
+    let mut payload = payload.clone();
+    if let Some(token) = payload.pointer_mut("/d/token") {
+        *token = Value::from("[redacted]");
+    }
+    payload
+}
+
+pub(crate) fn heartbeat(sequence_number: Option<u64>) -> Value {
+    json!({
+        "op": OpCode::Heartbeat,
+        "d": sequence_number,
+    })
+}
+
+pub(crate) fn identify(token: &str, intents: Intents, shard: Shard) -> Value {
+    json!({
+        "op": OpCode::Identify,
+        "d": {
+            "token": token,
+            "properties": {
+                "$os": std::env::consts::OS,
+                "$browser": "harmony",
+                "$device": "harmony",
+            },
+            "intents": intents,
+            "shard": shard,
+        }
+    })
+}
+
+pub(crate) fn resume(
+    token: &str,
+    session_id: &Option<String>,
+    sequence_number: Option<u64>,
+) -> Value {
+    json!({
+        "op": OpCode::Resume,
+        "d": {
+            "token": token,
+            "session_id": session_id,
+            "seq": sequence_number
+        }
+    })
+}
+
+pub(crate) fn presence_update(status: Status, activity: Option<Activity>) -> Value {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    json!({
+        "op": OpCode::PresenceUpdate,
+        "d": {
+            "since": now,
+            "activities": activity.into_iter().collect::<Vec<_>>(),
+            "status": status,
+            "afk": false
+        }
+    })
+}
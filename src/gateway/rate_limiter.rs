@@ -0,0 +1,79 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+const LIMIT: usize = 120;
+const WINDOW: Duration = Duration::from_secs(60);
+// Kept free for heartbeats, identifies and resumes so that commands can never
+// get the connection closed by starving them.
+const RESERVED: usize = 5;
+
+// Sliding window over the payloads sent on one connection.
+#[derive(Debug, Default)]
+pub(crate) struct RateLimiter {
+    sent: VecDeque<Instant>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    // How long to wait before a payload can be sent, `None` if it can be sent
+    // right away. Only `reserved` payloads can use the reserved slots.
+    pub(crate) fn delay(&mut self, reserved: bool) -> Option<Duration> {
+        let now = Instant::now();
+        while matches!(self.sent.front(), Some(&sent) if sent + WINDOW <= now) {
+            self.sent.pop_front();
+        }
+        let limit = if reserved { LIMIT } else { LIMIT - RESERVED };
+        let index = self.sent.len().checked_sub(limit)?;
+        Some(self.sent[index] + WINDOW - now)
+    }
+
+    pub(crate) fn acquire(&mut self, reserved: bool) -> Result<(), Duration> {
+        match self.delay(reserved) {
+            Some(delay) => Err(delay),
+            None => {
+                self.sent.push_back(Instant::now());
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reserved_slots() {
+        let mut rate_limiter = RateLimiter::new();
+        for _ in 0..LIMIT - RESERVED {
+            assert!(rate_limiter.acquire(false).is_ok());
+        }
+        let delay = rate_limiter.acquire(false).unwrap_err();
+        assert!(delay > WINDOW - Duration::from_secs(1) && delay <= WINDOW);
+        for _ in 0..RESERVED {
+            assert!(rate_limiter.acquire(true).is_ok());
+        }
+        assert!(rate_limiter.acquire(true).is_err());
+        assert!(rate_limiter.acquire(false).is_err());
+        assert_eq!(rate_limiter.sent.len(), LIMIT);
+    }
+
+    #[test]
+    fn window_slides() {
+        let mut rate_limiter = RateLimiter::new();
+        let now = Instant::now();
+        rate_limiter.sent.push_back(now - WINDOW);
+        rate_limiter
+            .sent
+            .extend((1..LIMIT).map(|_| now - Duration::from_secs(30)));
+        // The oldest payload has left the window, so a heartbeat fits again.
+        assert!(rate_limiter.acquire(true).is_ok());
+        assert!(rate_limiter.acquire(true).is_err());
+        // Commands wait until enough payloads leave the window to get below 115.
+        let delay = rate_limiter.delay(false).unwrap();
+        assert!(delay > Duration::from_secs(29) && delay <= Duration::from_secs(30));
+    }
+}
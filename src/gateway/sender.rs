@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use crossbeam_channel::Sender;
+use mio::Waker;
+use serde_json::Value;
+
+use super::{payload, Status};
+use crate::model::Activity;
+use crate::Result;
+
+// Queues commands for a `Gateway`, which writes them the next time it waits
+// for events. It does not need access to the gateway itself, so it can be used
+// while another thread is blocked reading from it.
+#[derive(Debug, Clone)]
+pub struct GatewaySender {
+    pub(crate) commands: Sender<Value>,
+    pub(crate) waker: Arc<Waker>,
+}
+
+impl GatewaySender {
+    pub fn presence_update(&self, status: Status, activity: Option<Activity>) -> Result {
+        self.send(payload::presence_update(status, activity))
+    }
+
+    pub(crate) fn send(&self, payload: Value) -> Result {
+        self.commands.send(payload)?;
+        self.wake()
+    }
+
+    // Interrupts a pending `Gateway::wait_events`.
+    pub(crate) fn wake(&self) -> Result {
+        self.waker.wake()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use serde::{Serialize, Serializer};
+
+use crate::model::id::GuildId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Shard {
+    pub id: u64,
+    pub count: u64,
+}
+
+impl Default for Shard {
+    fn default() -> Self {
+        Self { id: 0, count: 1 }
+    }
+}
+
+impl Shard {
+    pub fn new(id: u64, count: u64) -> Self {
+        Self { id, count }
+    }
+
+    pub fn for_guild(guild_id: GuildId, count: u64) -> Self {
+        Self {
+            id: (guild_id.0 >> 22) % count.max(1),
+            count,
+        }
+    }
+
+    pub fn contains(self, guild_id: GuildId) -> bool {
+        Self::for_guild(guild_id, self.count) == self
+    }
+}
+
+impl From<(u64, u64)> for Shard {
+    fn from((id, count): (u64, u64)) -> Self {
+        Self { id, count }
+    }
+}
+
+impl Serialize for Shard {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        [self.id, self.count].serialize(serializer)
+    }
+}
@@ -1,54 +1,96 @@
+#[cfg(feature = "async")]
+mod async_http;
+#[cfg(feature = "async")]
+mod async_rate_limiter;
 mod create_embed;
 mod create_guild_role;
 mod create_message;
 mod edit_message;
+mod endpoint;
 mod execute_webhook;
+mod rate_limit;
 mod rate_limiter;
+mod request;
+mod retry_policy;
 mod route;
 
-use std::fs::File;
 use std::path::Path;
 use std::sync::Arc;
 
-use multipart::client::lazy::Multipart;
-use serde_json::json;
-use ureq::Agent;
+use ureq::{Agent, Response};
 
 use crate::model::id::{ChannelId, GuildId, MessageId, RoleId, UserId, WebhookId};
-use crate::model::{Channel, Member, Message, Role};
-use crate::{Error, Result};
+use crate::model::{Channel, GatewayBot, Member, Message, Role};
+use crate::token::Token;
+use crate::{Config, Error, Result};
+#[cfg(feature = "async")]
+pub use async_http::AsyncHttp;
+#[cfg(feature = "async")]
+pub use async_rate_limiter::AsyncRateLimiter;
 pub use create_embed::CreateEmbed;
 pub use create_guild_role::CreateGuildRole;
 pub use create_message::CreateMessage;
 pub use edit_message::EditMessage;
 pub use execute_webhook::ExecuteWebhook;
 pub use rate_limiter::RateLimiter;
-pub use route::Route;
+pub use request::{Body, Method, Request};
+pub use retry_policy::RetryPolicy;
+pub use route::{MajorParameter, Route};
 
 #[derive(Debug, Clone)]
 pub struct Http {
     agent: Agent,
-    token: Arc<str>,
+    token: Token,
+    config: Arc<Config>,
     rate_limiter: RateLimiter,
 }
 
 impl Http {
     pub fn new(token: Arc<str>) -> Self {
+        Self::with_config(token, Config::default())
+    }
+
+    pub fn with_config(token: Arc<str>, config: Config) -> Self {
         Self {
             agent: Agent::new(),
-            token,
+            token: token.into(),
+            config: Arc::new(config),
             rate_limiter: RateLimiter::new(),
         }
     }
 
-    pub fn get_channel(&self, channel_id: ChannelId) -> Result<Option<Channel>> {
-        let request = self
+    pub fn request(&self, request: Request) -> Result<Response> {
+        let mut req = self
             .agent
-            .get(&api!("/channels/{}", channel_id.0))
+            .request(
+                request.route.method.as_str(),
+                &api!(self.config, request.path),
+            )
             .set("AUTHORIZATION", &self.token);
-        let channel = match self.rate_limiter.call(None, request) {
+        for (name, value) in &request.headers {
+            req = req.set(name, value);
+        }
+        if matches!(request.body, Body::Empty)
+            && matches!(
+                request.route.method,
+                Method::Post | Method::Put | Method::Patch
+            )
+        {
+            req = req.set("Content-Length", "0");
+        }
+        self.rate_limiter
+            .send(request.route, req, &request.body, &self.config.retry_policy)
+    }
+
+    pub fn get_gateway_bot(&self) -> Result<GatewayBot> {
+        let gateway_bot = self.request(Request::get_gateway_bot())?.into_json()?;
+        Ok(gateway_bot)
+    }
+
+    pub fn get_channel(&self, channel_id: ChannelId) -> Result<Option<Channel>> {
+        let channel = match self.request(Request::get_channel(channel_id)) {
             Ok(reponse) => Some(reponse.into_json()?),
-            Err(Error::Ureq(ureq::Error::Status(404, _))) => None,
+            Err(Error::Api { status: 404, .. }) => None,
             Err(err) => return Err(err),
         };
         Ok(channel)
@@ -59,15 +101,8 @@ impl Http {
         F: FnOnce(CreateMessage) -> CreateMessage,
     {
         let msg = f(CreateMessage::default());
-        let request = self
-            .agent
-            .post(&api!("/channels/{}/messages", channel_id.0))
-            .set("AUTHORIZATION", &self.token);
-        let json = serde_json::to_value(msg).unwrap();
-        let response =
-            self.rate_limiter
-                .send_json(Some(Route::Channel(channel_id)), request, json)?;
-        let message = response.into_json()?;
+        let request = Request::create_message(channel_id, msg)?;
+        let message = self.request(request)?.into_json()?;
         Ok(message)
     }
 
@@ -77,31 +112,8 @@ impl Http {
         P: AsRef<Path>,
     {
         let msg = f(CreateMessage::default());
-        let json = serde_json::to_value(msg).unwrap();
-        let mut m = Multipart::new();
-        for (i, file) in files.iter().enumerate() {
-            let mime = mime_guess::from_path(file).first_or_octet_stream();
-            m.add_stream(
-                format!("files[{}]", i),
-                File::open(file)?,
-                Some(file.as_ref().file_name().unwrap().to_string_lossy()),
-                Some(mime),
-            );
-        }
-        m.add_text("payload_json", json.to_string());
-        let mdata = m.prepare().unwrap();
-        let request = self
-            .agent
-            .post(&api!("/channels/{}/messages", channel_id.0))
-            .set("AUTHORIZATION", &self.token)
-            .set(
-                "Content-Type",
-                &format!("multipart/form-data; boundary={}", mdata.boundary()),
-            );
-        let response = self
-            .rate_limiter
-            .send(Some(Route::Channel(channel_id)), request, mdata)?;
-        let message = response.into_json()?;
+        let request = Request::send_files(channel_id, files, msg)?;
+        let message = self.request(request)?.into_json()?;
         Ok(message)
     }
 
@@ -115,89 +127,39 @@ impl Http {
         F: FnOnce(EditMessage) -> EditMessage,
     {
         let edit_msg = f(EditMessage::default());
-        let json = serde_json::to_value(edit_msg).unwrap();
-        let request = self
-            .agent
-            .request(
-                "PATCH",
-                &api!("/channels/{}/messages/{}", channel_id.0, message_id.0),
-            )
-            .set("AUTHORIZATION", &self.token);
-        let response = self.rate_limiter.send_json(
-            Some(Route::ChannelMessage(channel_id, message_id)),
-            request,
-            json,
-        )?;
-        let message = response.into_json()?;
+        let request = Request::edit_message(channel_id, message_id, edit_msg)?;
+        let message = self.request(request)?.into_json()?;
         Ok(message)
     }
 
     pub fn delete_message(&self, channel_id: ChannelId, message_id: MessageId) -> Result {
-        let request = self
-            .agent
-            .delete(&api!(
-                "/channels/{}/messages/{}",
-                channel_id.0,
-                message_id.0
-            ))
-            .set("AUTHORIZATION", &self.token);
-        self.rate_limiter
-            .call(Some(Route::ChannelMessage(channel_id, message_id)), request)?;
+        self.request(Request::delete_message(channel_id, message_id))?;
         Ok(())
     }
 
     pub fn get_guild_channels(&self, guild_id: GuildId) -> Result<Vec<Channel>> {
-        let request = self
-            .agent
-            .get(&api!("/guilds/{}/channels", guild_id.0))
-            .set("AUTHORIZATION", &self.token);
-        let response = self
-            .rate_limiter
-            .call(Some(Route::Guild(guild_id)), request)?;
+        let response = self.request(Request::get_guild_channels(guild_id))?;
         let channels = response.into_json()?;
         Ok(channels)
     }
 
     pub fn get_guild_member(&self, guild_id: GuildId, user_id: UserId) -> Result<Option<Member>> {
-        let request = self
-            .agent
-            .get(&api!("/guilds/{}/members/{}", guild_id.0, user_id.0))
-            .set("AUTHORIZATION", &self.token);
-        let member = match self
-            .rate_limiter
-            .call(Some(Route::Guild(guild_id)), request)
-        {
+        let member = match self.request(Request::get_guild_member(guild_id, user_id)) {
             Ok(response) => Some(response.into_json()?),
-            Err(Error::Ureq(ureq::Error::Status(404, _))) => None,
+            Err(Error::Api { status: 404, .. }) => None,
             Err(err) => return Err(err),
         };
         Ok(member)
     }
 
     pub fn list_guild_members(&self, guild_id: GuildId) -> Result<Vec<Member>> {
-        let request = self
-            .agent
-            .get(&api!("/guilds/{}/members?limit=1000", guild_id.0))
-            .set("AUTHORIZATION", &self.token);
-        let response = self
-            .rate_limiter
-            .call(Some(Route::Guild(guild_id)), request)?;
+        let response = self.request(Request::list_guild_members(guild_id))?;
         let members = response.into_json()?;
         Ok(members)
     }
 
     pub fn search_guild_members(&self, guild_id: GuildId, query: &str) -> Result<Vec<Member>> {
-        let request = self
-            .agent
-            .get(&api!(
-                "/guilds/{}/members/search?query={}&limit=1000",
-                guild_id.0,
-                query
-            ))
-            .set("AUTHORIZATION", &self.token);
-        let response = self
-            .rate_limiter
-            .call(Some(Route::Guild(guild_id)), request)?;
+        let response = self.request(Request::search_guild_members(guild_id, query))?;
         let members = response.into_json()?;
         Ok(members)
     }
@@ -208,18 +170,7 @@ impl Http {
         user_id: UserId,
         role_id: RoleId,
     ) -> Result {
-        let request = self
-            .agent
-            .put(&api!(
-                "/guilds/{}/members/{}/roles/{}",
-                guild_id.0,
-                user_id.0,
-                role_id.0
-            ))
-            .set("AUTHORIZATION", &self.token)
-            .set("Content-Length", "0");
-        self.rate_limiter
-            .call(Some(Route::GuildMember(guild_id, user_id)), request)?;
+        self.request(Request::add_guild_member_role(guild_id, user_id, role_id))?;
         Ok(())
     }
 
@@ -229,26 +180,14 @@ impl Http {
         user_id: UserId,
         role_id: RoleId,
     ) -> Result {
-        let request = self
-            .agent
-            .delete(&api!(
-                "/guilds/{}/members/{}/roles/{}",
-                guild_id.0,
-                user_id.0,
-                role_id.0
-            ))
-            .set("AUTHORIZATION", &self.token);
-        self.rate_limiter
-            .call(Some(Route::GuildMember(guild_id, user_id)), request)?;
+        self.request(Request::remove_guild_member_role(
+            guild_id, user_id, role_id,
+        ))?;
         Ok(())
     }
 
     pub fn get_guild_roles(&self, guild_id: GuildId) -> Result<Vec<Role>> {
-        let request = self
-            .agent
-            .get(&api!("/guilds/{}/roles", guild_id.0))
-            .set("AUTHORIZATION", &self.token);
-        let response = self.rate_limiter.call(None, request)?;
+        let response = self.request(Request::get_guild_roles(guild_id))?;
         let roles = response.into_json()?;
         Ok(roles)
     }
@@ -258,35 +197,18 @@ impl Http {
         F: FnOnce(CreateGuildRole) -> CreateGuildRole,
     {
         let role = f(CreateGuildRole::default());
-        let json = serde_json::to_value(role).unwrap();
-        let request = self
-            .agent
-            .post(&api!("/guilds/{}/roles", guild_id.0))
-            .set("AUTHORIZATION", &self.token);
-        let response = self
-            .rate_limiter
-            .send_json(Some(Route::Guild(guild_id)), request, json)?;
-        let role = response.into_json()?;
+        let request = Request::create_guild_role(guild_id, role)?;
+        let role = self.request(request)?.into_json()?;
         Ok(role)
     }
 
     pub fn delete_guild_role(&self, guild_id: GuildId, role_id: RoleId) -> Result {
-        let request = self
-            .agent
-            .delete(&api!("/guilds/{}/roles/{}", guild_id.0, role_id.0))
-            .set("AUTHORIZATION", &self.token);
-        self.rate_limiter.call(None, request)?;
+        self.request(Request::delete_guild_role(guild_id, role_id))?;
         Ok(())
     }
 
     pub fn create_dm(&self, user_id: UserId) -> Result<Channel> {
-        let request = self
-            .agent
-            .post(&api!("/users/@me/channels"))
-            .set("AUTHORIZATION", &self.token);
-        let json = json!({ "recipient_id": user_id });
-        let response = self.rate_limiter.send_json(None, request, json)?;
-        let channel = response.into_json()?;
+        let channel = self.request(Request::create_dm(user_id))?.into_json()?;
         Ok(channel)
     }
 
@@ -301,19 +223,8 @@ impl Http {
         F: FnOnce(ExecuteWebhook) -> ExecuteWebhook,
     {
         let msg = f(ExecuteWebhook::default());
-        let json = serde_json::to_value(msg).unwrap();
-        let request = self
-            .agent
-            .post(&api!(
-                "/webhooks/{}/{}?wait={}",
-                webhook_id.0,
-                webhook_token,
-                wait
-            ))
-            .set("AUTHORIZATION", &self.token);
-        let response =
-            self.rate_limiter
-                .send_json(Some(Route::Webhook(webhook_id)), request, json)?;
+        let request = Request::execute_webhook(webhook_id, webhook_token, wait, msg)?;
+        let response = self.request(request)?;
         Ok(if wait {
             let message = response.into_json()?;
             Some(message)
@@ -328,17 +239,8 @@ impl Http {
         webhook_token: &str,
         message_id: MessageId,
     ) -> Result {
-        let request = self
-            .agent
-            .delete(&api!(
-                "/webhooks/{}/{}/messages/{}",
-                webhook_id.0,
-                webhook_token,
-                message_id.0
-            ))
-            .set("AUTHORIZATION", &self.token);
-        self.rate_limiter
-            .call(Some(Route::Webhook(webhook_id)), request)?;
+        let request = Request::delete_webhook_message(webhook_id, webhook_token, message_id);
+        self.request(request)?;
         Ok(())
     }
 }
@@ -0,0 +1,280 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use reqwest::{Client, RequestBuilder, Response};
+
+use super::{
+    AsyncRateLimiter, Body, CreateGuildRole, CreateMessage, EditMessage, ExecuteWebhook, Method,
+    Request,
+};
+use crate::model::id::{ChannelId, GuildId, MessageId, RoleId, UserId, WebhookId};
+use crate::model::{Channel, GatewayBot, Member, Message, Role};
+use crate::token::Token;
+use crate::{Config, Error, Result};
+
+#[derive(Debug, Clone)]
+pub struct AsyncHttp {
+    client: Client,
+    token: Token,
+    config: Arc<Config>,
+    rate_limiter: AsyncRateLimiter,
+}
+
+impl AsyncHttp {
+    pub fn new(token: Arc<str>) -> Self {
+        Self::with_config(token, Config::default())
+    }
+
+    pub fn with_config(token: Arc<str>, config: Config) -> Self {
+        Self {
+            client: Client::new(),
+            token: token.into(),
+            config: Arc::new(config),
+            rate_limiter: AsyncRateLimiter::new(),
+        }
+    }
+
+    pub(crate) fn token(&self) -> &Token {
+        &self.token
+    }
+
+    pub async fn request(&self, request: Request) -> Result<Response> {
+        let url = api!(self.config, request.path);
+        let build = || self.build(&request, &url);
+        self.rate_limiter
+            .send(request.route, build, &self.config.retry_policy)
+            .await
+    }
+
+    fn build(&self, request: &Request, url: &str) -> RequestBuilder {
+        let method = match request.route.method {
+            Method::Get => reqwest::Method::GET,
+            Method::Post => reqwest::Method::POST,
+            Method::Put => reqwest::Method::PUT,
+            Method::Patch => reqwest::Method::PATCH,
+            Method::Delete => reqwest::Method::DELETE,
+        };
+        let mut req = self
+            .client
+            .request(method, url)
+            .header("AUTHORIZATION", &*self.token);
+        for (name, value) in &request.headers {
+            req = req.header(name, value);
+        }
+        match &request.body {
+            Body::Empty => match request.route.method {
+                Method::Post | Method::Put | Method::Patch => req.body(Vec::new()),
+                _ => req,
+            },
+            Body::Json(json) => req.json(json),
+            Body::Multipart { boundary, data } => req
+                .header(
+                    "Content-Type",
+                    format!("multipart/form-data; boundary={}", boundary),
+                )
+                .body(data.clone()),
+        }
+    }
+
+    pub async fn get_gateway_bot(&self) -> Result<GatewayBot> {
+        let gateway_bot = self
+            .request(Request::get_gateway_bot())
+            .await?
+            .json()
+            .await?;
+        Ok(gateway_bot)
+    }
+
+    pub async fn get_channel(&self, channel_id: ChannelId) -> Result<Option<Channel>> {
+        let channel = match self.request(Request::get_channel(channel_id)).await {
+            Ok(reponse) => Some(reponse.json().await?),
+            Err(Error::Api { status: 404, .. }) => None,
+            Err(err) => return Err(err),
+        };
+        Ok(channel)
+    }
+
+    pub async fn create_message<F>(&self, channel_id: ChannelId, f: F) -> Result<Message>
+    where
+        F: FnOnce(CreateMessage) -> CreateMessage,
+    {
+        let msg = f(CreateMessage::default());
+        let request = Request::create_message(channel_id, msg)?;
+        let message = self.request(request).await?.json().await?;
+        Ok(message)
+    }
+
+    pub async fn send_files<F, P>(
+        &self,
+        channel_id: ChannelId,
+        files: &[P],
+        f: F,
+    ) -> Result<Message>
+    where
+        F: FnOnce(CreateMessage) -> CreateMessage,
+        P: AsRef<Path>,
+    {
+        let msg = f(CreateMessage::default());
+        // Reading the files blocks, so it is kept off the runtime's workers.
+        let files = files
+            .iter()
+            .map(|file| file.as_ref().to_owned())
+            .collect::<Vec<PathBuf>>();
+        let build =
+            tokio::task::spawn_blocking(move || Request::send_files(channel_id, &files, msg));
+        let request = match build.await {
+            Ok(request) => request?,
+            Err(err) => std::panic::resume_unwind(err.into_panic()),
+        };
+        let message = self.request(request).await?.json().await?;
+        Ok(message)
+    }
+
+    pub async fn edit_message<F>(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        f: F,
+    ) -> Result<Message>
+    where
+        F: FnOnce(EditMessage) -> EditMessage,
+    {
+        let edit_msg = f(EditMessage::default());
+        let request = Request::edit_message(channel_id, message_id, edit_msg)?;
+        let message = self.request(request).await?.json().await?;
+        Ok(message)
+    }
+
+    pub async fn delete_message(&self, channel_id: ChannelId, message_id: MessageId) -> Result {
+        self.request(Request::delete_message(channel_id, message_id))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_guild_channels(&self, guild_id: GuildId) -> Result<Vec<Channel>> {
+        let response = self.request(Request::get_guild_channels(guild_id)).await?;
+        let channels = response.json().await?;
+        Ok(channels)
+    }
+
+    pub async fn get_guild_member(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<Member>> {
+        let member = match self
+            .request(Request::get_guild_member(guild_id, user_id))
+            .await
+        {
+            Ok(response) => Some(response.json().await?),
+            Err(Error::Api { status: 404, .. }) => None,
+            Err(err) => return Err(err),
+        };
+        Ok(member)
+    }
+
+    pub async fn list_guild_members(&self, guild_id: GuildId) -> Result<Vec<Member>> {
+        let response = self.request(Request::list_guild_members(guild_id)).await?;
+        let members = response.json().await?;
+        Ok(members)
+    }
+
+    pub async fn search_guild_members(
+        &self,
+        guild_id: GuildId,
+        query: &str,
+    ) -> Result<Vec<Member>> {
+        let response = self
+            .request(Request::search_guild_members(guild_id, query))
+            .await?;
+        let members = response.json().await?;
+        Ok(members)
+    }
+
+    pub async fn add_guild_member_role(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        role_id: RoleId,
+    ) -> Result {
+        self.request(Request::add_guild_member_role(guild_id, user_id, role_id))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn remove_guild_member_role(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        role_id: RoleId,
+    ) -> Result {
+        self.request(Request::remove_guild_member_role(
+            guild_id, user_id, role_id,
+        ))
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_guild_roles(&self, guild_id: GuildId) -> Result<Vec<Role>> {
+        let response = self.request(Request::get_guild_roles(guild_id)).await?;
+        let roles = response.json().await?;
+        Ok(roles)
+    }
+
+    pub async fn create_guild_role<F>(&self, guild_id: GuildId, f: F) -> Result<Role>
+    where
+        F: FnOnce(CreateGuildRole) -> CreateGuildRole,
+    {
+        let role = f(CreateGuildRole::default());
+        let request = Request::create_guild_role(guild_id, role)?;
+        let role = self.request(request).await?.json().await?;
+        Ok(role)
+    }
+
+    pub async fn delete_guild_role(&self, guild_id: GuildId, role_id: RoleId) -> Result {
+        self.request(Request::delete_guild_role(guild_id, role_id))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn create_dm(&self, user_id: UserId) -> Result<Channel> {
+        let channel = self
+            .request(Request::create_dm(user_id))
+            .await?
+            .json()
+            .await?;
+        Ok(channel)
+    }
+
+    pub async fn execute_webhook<F>(
+        &self,
+        webhook_id: WebhookId,
+        webhook_token: &str,
+        wait: bool,
+        f: F,
+    ) -> Result<Option<Message>>
+    where
+        F: FnOnce(ExecuteWebhook) -> ExecuteWebhook,
+    {
+        let msg = f(ExecuteWebhook::default());
+        let request = Request::execute_webhook(webhook_id, webhook_token, wait, msg)?;
+        let response = self.request(request).await?;
+        Ok(if wait {
+            let message = response.json().await?;
+            Some(message)
+        } else {
+            None
+        })
+    }
+
+    pub async fn delete_webhook_message(
+        &self,
+        webhook_id: WebhookId,
+        webhook_token: &str,
+        message_id: MessageId,
+    ) -> Result {
+        let request = Request::delete_webhook_message(webhook_id, webhook_token, message_id);
+        self.request(request).await?;
+        Ok(())
+    }
+}
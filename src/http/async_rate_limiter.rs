@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use reqwest::{RequestBuilder, Response};
+use tokio::sync::Mutex as AsyncMutex;
+
+use super::rate_limit::{self, BucketKey, Buckets, GlobalRateLimit, RateLimit, TooManyRequests};
+use super::retry_policy::Failure;
+use super::{Method, RetryPolicy, Route};
+use crate::{Error, Result};
+
+#[derive(Debug, Default, Clone)]
+pub struct AsyncRateLimiter {
+    buckets: Arc<Mutex<Buckets>>,
+    rate_limits: Arc<Mutex<HashMap<BucketKey, Arc<AsyncMutex<RateLimit>>>>>,
+    global: Arc<Mutex<GlobalRateLimit>>,
+}
+
+impl AsyncRateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self, key: BucketKey) -> Arc<AsyncMutex<RateLimit>> {
+        let mut guard = self.rate_limits.lock();
+        guard.entry(key).or_default().clone()
+    }
+
+    pub async fn send<F>(
+        &self,
+        route: Route,
+        build: F,
+        retry_policy: &RetryPolicy,
+    ) -> Result<Response>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let key = self.buckets.lock().key(route);
+        let mutex = self.lock(key);
+        let mut rate_limit = mutex.lock().await;
+        if let Some(delay) = rate_limit.delay() {
+            tokio::time::sleep(delay).await;
+        }
+        let response = self.retry(route.method, build, retry_policy).await?;
+        let new_rate_limit = rate_limit.update_or_reset(|name| header(&response, name));
+        match header(&response, "x-ratelimit-bucket") {
+            Some(bucket) => {
+                let key = self.buckets.lock().learn(route, bucket);
+                let bucket_mutex = self.lock(key);
+                if Arc::ptr_eq(&bucket_mutex, &mutex) {
+                    *rate_limit = new_rate_limit;
+                } else {
+                    drop(rate_limit);
+                    *bucket_mutex.lock().await = new_rate_limit;
+                }
+            }
+            None => *rate_limit = new_rate_limit,
+        }
+        Ok(response)
+    }
+
+    async fn wait_global(&self) {
+        loop {
+            let delay = self.global.lock().acquire();
+            match delay {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => return,
+            }
+        }
+    }
+
+    async fn retry<F>(
+        &self,
+        method: Method,
+        build: F,
+        retry_policy: &RetryPolicy,
+    ) -> Result<Response>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let mut attempt = 0;
+        loop {
+            self.wait_global().await;
+            match build().send().await {
+                Ok(response) if response.status().is_success() => return Ok(response),
+                Ok(response) if response.status().as_u16() == 429 => {
+                    let global = rate_limit::is_global(|name| header(&response, name));
+                    let too_many_requests = response.json::<TooManyRequests>().await?;
+                    let retry_after = too_many_requests.retry_after()?;
+                    if global || too_many_requests.global {
+                        self.global.lock().block(retry_after);
+                    } else {
+                        tokio::time::sleep(retry_after).await;
+                    }
+                    continue;
+                }
+                Ok(response) => {
+                    let status = response.status().as_u16();
+                    attempt += 1;
+                    if !retry_policy.should_retry(method, attempt, Failure::Status(status)) {
+                        return Err(Error::from_body(status, response.text().await?));
+                    }
+                }
+                Err(err) => {
+                    attempt += 1;
+                    if !retry_policy.should_retry(method, attempt, Failure::from(&err)) {
+                        return Err(err.into());
+                    }
+                }
+            }
+            tokio::time::sleep(retry_policy.delay(attempt)).await;
+        }
+    }
+}
+
+fn header<'a>(response: &'a Response, name: &'static str) -> Option<&'a str> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+}
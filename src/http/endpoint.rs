@@ -0,0 +1,233 @@
+use std::fs::File;
+use std::path::Path;
+
+use multipart::client::lazy::Multipart;
+use serde_json::json;
+
+use super::{
+    Body, CreateGuildRole, CreateMessage, EditMessage, ExecuteWebhook, MajorParameter, Method,
+    Request, Route,
+};
+use crate::model::id::{ChannelId, GuildId, MessageId, RoleId, UserId, WebhookId};
+use crate::Result;
+
+// Requests shared by `Http` and `AsyncHttp`.
+impl Request {
+    pub(crate) fn get_gateway_bot() -> Self {
+        Self::new(
+            Route::new(Method::Get, "/gateway/bot", MajorParameter::None),
+            "/gateway/bot",
+        )
+    }
+
+    pub(crate) fn get_channel(channel_id: ChannelId) -> Self {
+        Self::new(
+            Route::new(Method::Get, "/channels/{channel_id}", channel_id),
+            format!("/channels/{}", channel_id.0),
+        )
+    }
+
+    pub(crate) fn create_message(channel_id: ChannelId, msg: CreateMessage) -> Result<Self> {
+        let json = serde_json::to_value(msg)?;
+        Ok(Self::new(
+            Route::new(Method::Post, "/channels/{channel_id}/messages", channel_id),
+            format!("/channels/{}/messages", channel_id.0),
+        )
+        .json(json))
+    }
+
+    pub(crate) fn send_files<P: AsRef<Path>>(
+        channel_id: ChannelId,
+        files: &[P],
+        msg: CreateMessage,
+    ) -> Result<Self> {
+        let json = serde_json::to_value(msg)?;
+        let mut m = Multipart::new();
+        for (i, file) in files.iter().enumerate() {
+            let mime = mime_guess::from_path(file).first_or_octet_stream();
+            m.add_stream(
+                format!("files[{}]", i),
+                File::open(file)?,
+                file.as_ref().file_name().map(|x| x.to_string_lossy()),
+                Some(mime),
+            );
+        }
+        m.add_text("payload_json", json.to_string());
+        let mdata = m.prepare().map_err(|err| err.error)?;
+        Ok(Self::new(
+            Route::new(Method::Post, "/channels/{channel_id}/messages", channel_id),
+            format!("/channels/{}/messages", channel_id.0),
+        )
+        .body(Body::multipart(mdata)?))
+    }
+
+    pub(crate) fn edit_message(
+        channel_id: ChannelId,
+        message_id: MessageId,
+        edit_msg: EditMessage,
+    ) -> Result<Self> {
+        let json = serde_json::to_value(edit_msg)?;
+        Ok(Self::new(
+            Route::new(
+                Method::Patch,
+                "/channels/{channel_id}/messages/{message_id}",
+                channel_id,
+            ),
+            format!("/channels/{}/messages/{}", channel_id.0, message_id.0),
+        )
+        .json(json))
+    }
+
+    pub(crate) fn delete_message(channel_id: ChannelId, message_id: MessageId) -> Self {
+        Self::new(
+            Route::new(
+                Method::Delete,
+                "/channels/{channel_id}/messages/{message_id}",
+                channel_id,
+            ),
+            format!("/channels/{}/messages/{}", channel_id.0, message_id.0),
+        )
+    }
+
+    pub(crate) fn get_guild_channels(guild_id: GuildId) -> Self {
+        Self::new(
+            Route::new(Method::Get, "/guilds/{guild_id}/channels", guild_id),
+            format!("/guilds/{}/channels", guild_id.0),
+        )
+    }
+
+    pub(crate) fn get_guild_member(guild_id: GuildId, user_id: UserId) -> Self {
+        Self::new(
+            Route::new(
+                Method::Get,
+                "/guilds/{guild_id}/members/{user_id}",
+                guild_id,
+            ),
+            format!("/guilds/{}/members/{}", guild_id.0, user_id.0),
+        )
+    }
+
+    pub(crate) fn list_guild_members(guild_id: GuildId) -> Self {
+        Self::new(
+            Route::new(Method::Get, "/guilds/{guild_id}/members", guild_id),
+            format!("/guilds/{}/members?limit=1000", guild_id.0),
+        )
+    }
+
+    pub(crate) fn search_guild_members(guild_id: GuildId, query: &str) -> Self {
+        Self::new(
+            Route::new(Method::Get, "/guilds/{guild_id}/members/search", guild_id),
+            format!(
+                "/guilds/{}/members/search?query={}&limit=1000",
+                guild_id.0, query
+            ),
+        )
+    }
+
+    pub(crate) fn add_guild_member_role(
+        guild_id: GuildId,
+        user_id: UserId,
+        role_id: RoleId,
+    ) -> Self {
+        Self::new(
+            Route::new(
+                Method::Put,
+                "/guilds/{guild_id}/members/{user_id}/roles/{role_id}",
+                guild_id,
+            ),
+            format!(
+                "/guilds/{}/members/{}/roles/{}",
+                guild_id.0, user_id.0, role_id.0
+            ),
+        )
+    }
+
+    pub(crate) fn remove_guild_member_role(
+        guild_id: GuildId,
+        user_id: UserId,
+        role_id: RoleId,
+    ) -> Self {
+        Self::new(
+            Route::new(
+                Method::Delete,
+                "/guilds/{guild_id}/members/{user_id}/roles/{role_id}",
+                guild_id,
+            ),
+            format!(
+                "/guilds/{}/members/{}/roles/{}",
+                guild_id.0, user_id.0, role_id.0
+            ),
+        )
+    }
+
+    pub(crate) fn get_guild_roles(guild_id: GuildId) -> Self {
+        Self::new(
+            Route::new(Method::Get, "/guilds/{guild_id}/roles", guild_id),
+            format!("/guilds/{}/roles", guild_id.0),
+        )
+    }
+
+    pub(crate) fn create_guild_role(guild_id: GuildId, role: CreateGuildRole) -> Result<Self> {
+        let json = serde_json::to_value(role)?;
+        Ok(Self::new(
+            Route::new(Method::Post, "/guilds/{guild_id}/roles", guild_id),
+            format!("/guilds/{}/roles", guild_id.0),
+        )
+        .json(json))
+    }
+
+    pub(crate) fn delete_guild_role(guild_id: GuildId, role_id: RoleId) -> Self {
+        Self::new(
+            Route::new(
+                Method::Delete,
+                "/guilds/{guild_id}/roles/{role_id}",
+                guild_id,
+            ),
+            format!("/guilds/{}/roles/{}", guild_id.0, role_id.0),
+        )
+    }
+
+    pub(crate) fn create_dm(user_id: UserId) -> Self {
+        Self::new(
+            Route::new(Method::Post, "/users/@me/channels", MajorParameter::None),
+            "/users/@me/channels",
+        )
+        .json(json!({ "recipient_id": user_id }))
+    }
+
+    pub(crate) fn execute_webhook(
+        webhook_id: WebhookId,
+        webhook_token: &str,
+        wait: bool,
+        msg: ExecuteWebhook,
+    ) -> Result<Self> {
+        let json = serde_json::to_value(msg)?;
+        Ok(Self::new(
+            Route::new(
+                Method::Post,
+                "/webhooks/{webhook_id}/{webhook_token}",
+                webhook_id,
+            ),
+            format!("/webhooks/{}/{}?wait={}", webhook_id.0, webhook_token, wait),
+        )
+        .json(json))
+    }
+
+    pub(crate) fn delete_webhook_message(
+        webhook_id: WebhookId,
+        webhook_token: &str,
+        message_id: MessageId,
+    ) -> Self {
+        Self::new(
+            Route::new(
+                Method::Delete,
+                "/webhooks/{webhook_id}/{webhook_token}/messages/{message_id}",
+                webhook_id,
+            ),
+            format!(
+                "/webhooks/{}/{}/messages/{}",
+                webhook_id.0, webhook_token, message_id.0
+            ),
+        )
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use serde::Deserialize;
+use tracing::warn;
+
+use super::{MajorParameter, Method, Route};
+use crate::{Error, Result};
+
+const GLOBAL_LIMIT: u32 = 50;
+const GLOBAL_WINDOW: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum BucketKey {
+    Route(Route),
+    Bucket(Arc<str>, MajorParameter),
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Buckets(HashMap<(Method, &'static str), Arc<str>>);
+
+impl Buckets {
+    pub fn key(&self, route: Route) -> BucketKey {
+        match self.0.get(&(route.method, route.path)) {
+            Some(bucket) => BucketKey::Bucket(bucket.clone(), route.major),
+            None => BucketKey::Route(route),
+        }
+    }
+
+    pub fn learn(&mut self, route: Route, bucket: &str) -> BucketKey {
+        let entry = self
+            .0
+            .entry((route.method, route.path))
+            .or_insert_with(|| Arc::from(bucket));
+        if &**entry != bucket {
+            *entry = Arc::from(bucket);
+        }
+        BucketKey::Bucket(entry.clone(), route.major)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct RateLimit {
+    limit: u64,
+    remaining: u64,
+    reset: SystemTime,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            limit: 0,
+            remaining: 1,
+            reset: SystemTime::UNIX_EPOCH,
+        }
+    }
+}
+
+impl RateLimit {
+    pub fn delay(&self) -> Option<Duration> {
+        if self.remaining == 0 {
+            self.reset.duration_since(SystemTime::now()).ok()
+        } else {
+            None
+        }
+    }
+
+    // The request has already gone through when the headers are read, so
+    // invalid headers only cost the state of the bucket, not the response.
+    pub fn update_or_reset<'a, F>(&self, header: F) -> Self
+    where
+        F: Fn(&'static str) -> Option<&'a str>,
+    {
+        self.update(header).unwrap_or_else(|err| {
+            warn!(error = %err, "invalid rate limit headers, resetting the bucket");
+            Self::default()
+        })
+    }
+
+    pub fn update<'a, F>(&self, header: F) -> Result<Self>
+    where
+        F: Fn(&'static str) -> Option<&'a str>,
+    {
+        let limit = parse_header(&header, "x-ratelimit-limit")?.unwrap_or(self.limit);
+        let remaining = parse_header(&header, "x-ratelimit-remaining")?.unwrap_or(self.remaining);
+        let reset = match parse_header(&header, "x-ratelimit-reset")? {
+            Some(reset) => SystemTime::UNIX_EPOCH + parse_secs("x-ratelimit-reset", reset)?,
+            None => self.reset,
+        };
+        Ok(Self {
+            limit,
+            remaining,
+            reset,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct GlobalRateLimit {
+    blocked_until: SystemTime,
+    window_start: SystemTime,
+    count: u32,
+}
+
+impl Default for GlobalRateLimit {
+    fn default() -> Self {
+        Self {
+            blocked_until: SystemTime::UNIX_EPOCH,
+            window_start: SystemTime::UNIX_EPOCH,
+            count: 0,
+        }
+    }
+}
+
+impl GlobalRateLimit {
+    // Takes a slot in the current window, or returns how long to wait before trying again.
+    pub fn acquire(&mut self) -> Option<Duration> {
+        let now = SystemTime::now();
+        if let Ok(delay) = self.blocked_until.duration_since(now) {
+            if !delay.is_zero() {
+                return Some(delay);
+            }
+        }
+        if self.window_start + GLOBAL_WINDOW <= now {
+            self.window_start = now;
+            self.count = 0;
+        }
+        if self.count < GLOBAL_LIMIT {
+            self.count += 1;
+            return None;
+        }
+        Some(
+            (self.window_start + GLOBAL_WINDOW)
+                .duration_since(now)
+                .unwrap_or_default(),
+        )
+    }
+
+    pub fn block(&mut self, retry_after: Duration) {
+        self.blocked_until = self.blocked_until.max(SystemTime::now() + retry_after);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct TooManyRequests {
+    retry_after: f64,
+    #[serde(default)]
+    pub global: bool,
+}
+
+impl TooManyRequests {
+    pub fn retry_after(&self) -> Result<Duration> {
+        parse_secs("retry_after", self.retry_after)
+    }
+}
+
+pub(crate) fn is_global<'a, F>(header: F) -> bool
+where
+    F: Fn(&'static str) -> Option<&'a str>,
+{
+    header("x-ratelimit-global") == Some("true") || header("x-ratelimit-scope") == Some("global")
+}
+
+fn parse_header<'a, F, T>(header: &F, name: &'static str) -> Result<Option<T>>
+where
+    F: Fn(&'static str) -> Option<&'a str>,
+    T: FromStr,
+{
+    header(name)
+        .map(|value| {
+            value.parse().map_err(|_| Error::InvalidRateLimit {
+                name,
+                value: value.to_string(),
+            })
+        })
+        .transpose()
+}
+
+fn parse_secs(name: &'static str, secs: f64) -> Result<Duration> {
+    Duration::try_from_secs_f64(secs).map_err(|_| Error::InvalidRateLimit {
+        name,
+        value: secs.to_string(),
+    })
+}
@@ -1,19 +1,20 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::thread;
-use std::time::{Duration, SystemTime};
 
-use multipart::client::lazy::PreparedFields;
 use parking_lot::Mutex;
-use serde::Deserialize;
 use ureq::{Request, Response};
 
-use super::Route;
-use crate::Result;
+use super::rate_limit::{self, BucketKey, Buckets, GlobalRateLimit, RateLimit, TooManyRequests};
+use super::retry_policy::Failure;
+use super::{Body, Method, RetryPolicy, Route};
+use crate::{Error, Result};
 
 #[derive(Debug, Default, Clone)]
 pub struct RateLimiter {
-    routes: Arc<Mutex<HashMap<Route, Arc<Mutex<RateLimit>>>>>,
+    buckets: Arc<Mutex<Buckets>>,
+    rate_limits: Arc<Mutex<HashMap<BucketKey, Arc<Mutex<RateLimit>>>>>,
+    global: Arc<Mutex<GlobalRateLimit>>,
 }
 
 impl RateLimiter {
@@ -21,195 +22,98 @@ impl RateLimiter {
         Self::default()
     }
 
-    fn lock(&self, route: Route) -> Arc<Mutex<RateLimit>> {
-        let mut guard = self.routes.lock();
-        guard
-            .entry(route)
-            .or_insert_with(|| {
-                Arc::new(Mutex::new(RateLimit {
-                    limit: 0,
-                    remaining: 1,
-                    reset: SystemTime::UNIX_EPOCH,
-                }))
-            })
-            .clone()
-    }
-
-    pub fn call(&self, route: Option<Route>, request: Request) -> Result<Response> {
-        let response = if let Some(route) = route {
-            let mutex = self.lock(route);
-            let mut rate_limit = mutex.lock();
-            if rate_limit.remaining == 0 {
-                if let Ok(delay) = rate_limit.reset.duration_since(SystemTime::now()) {
-                    thread::sleep(delay);
-                }
-            }
-            let response = loop {
-                match request.clone().call() {
-                    Ok(response) => break response,
-                    Err(ureq::Error::Status(429, response)) => {
-                        let too_many_requests = response.into_json::<TooManyRequests>()?;
-                        thread::sleep(Duration::from_secs_f64(too_many_requests.retry_after));
-                    }
-                    Err(err) => return Err(err.into()),
-                }
-            };
-            let limit = response
-                .header("x-ratelimit-limit")
-                .map(|x| x.parse::<u64>().unwrap())
-                .unwrap_or(rate_limit.limit);
-            let remaining = response
-                .header("x-ratelimit-remaining")
-                .map(|x| x.parse::<u64>().unwrap())
-                .unwrap_or(rate_limit.remaining);
-            let reset = response
-                .header("x-ratelimit-reset")
-                .map(|x| std::time::UNIX_EPOCH + Duration::from_secs_f64(x.parse().unwrap()))
-                .unwrap_or(rate_limit.reset);
-            *rate_limit = RateLimit {
-                limit,
-                remaining,
-                reset,
-            };
-            response
-        } else {
-            loop {
-                match request.clone().call() {
-                    Ok(response) => break response,
-                    Err(ureq::Error::Status(429, response)) => {
-                        let too_many_requests = response.into_json::<TooManyRequests>()?;
-                        thread::sleep(Duration::from_secs_f64(too_many_requests.retry_after));
-                    }
-                    Err(err) => return Err(err.into()),
-                }
-            }
-        };
-        Ok(response)
+    fn lock(&self, key: BucketKey) -> Arc<Mutex<RateLimit>> {
+        let mut guard = self.rate_limits.lock();
+        guard.entry(key).or_default().clone()
     }
 
     pub fn send(
         &self,
-        route: Option<Route>,
+        route: Route,
         request: Request,
-        mut fields: PreparedFields,
+        body: &Body,
+        retry_policy: &RetryPolicy,
     ) -> Result<Response> {
-        let response = if let Some(route) = route {
-            let mutex = self.lock(route);
-            let mut rate_limit = mutex.lock();
-            if rate_limit.remaining == 0 {
-                if let Ok(delay) = rate_limit.reset.duration_since(SystemTime::now()) {
-                    thread::sleep(delay);
-                }
-            }
-            let response = loop {
-                match request.clone().send(&mut fields) {
-                    Ok(response) => break response,
-                    Err(ureq::Error::Status(429, response)) => {
-                        let too_many_requests = response.into_json::<TooManyRequests>()?;
-                        thread::sleep(Duration::from_secs_f64(too_many_requests.retry_after));
-                    }
-                    Err(err) => return Err(err.into()),
-                }
-            };
-            let limit = response
-                .header("x-ratelimit-limit")
-                .map(|x| x.parse::<u64>().unwrap())
-                .unwrap_or(rate_limit.limit);
-            let remaining = response
-                .header("x-ratelimit-remaining")
-                .map(|x| x.parse::<u64>().unwrap())
-                .unwrap_or(rate_limit.remaining);
-            let reset = response
-                .header("x-ratelimit-reset")
-                .map(|x| std::time::UNIX_EPOCH + Duration::from_secs_f64(x.parse().unwrap()))
-                .unwrap_or(rate_limit.reset);
-            *rate_limit = RateLimit {
-                limit,
-                remaining,
-                reset,
-            };
-            response
-        } else {
-            loop {
-                match request.clone().send(&mut fields) {
-                    Ok(response) => break response,
-                    Err(ureq::Error::Status(429, response)) => {
-                        let too_many_requests = response.into_json::<TooManyRequests>()?;
-                        thread::sleep(Duration::from_secs_f64(too_many_requests.retry_after));
-                    }
-                    Err(err) => return Err(err.into()),
+        let key = self.buckets.lock().key(route);
+        let mutex = self.lock(key);
+        let mut rate_limit = mutex.lock();
+        if let Some(delay) = rate_limit.delay() {
+            thread::sleep(delay);
+        }
+        let response = self.retry(route.method, request, body, retry_policy)?;
+        let new_rate_limit = rate_limit.update_or_reset(|name| response.header(name));
+        match response.header("x-ratelimit-bucket") {
+            Some(bucket) => {
+                let key = self.buckets.lock().learn(route, bucket);
+                let bucket_mutex = self.lock(key);
+                if Arc::ptr_eq(&bucket_mutex, &mutex) {
+                    *rate_limit = new_rate_limit;
+                } else {
+                    drop(rate_limit);
+                    *bucket_mutex.lock() = new_rate_limit;
                 }
             }
-        };
+            None => *rate_limit = new_rate_limit,
+        }
         Ok(response)
     }
 
-    pub fn send_json(
+    fn wait_global(&self) {
+        loop {
+            let delay = self.global.lock().acquire();
+            match delay {
+                Some(delay) => thread::sleep(delay),
+                None => return,
+            }
+        }
+    }
+
+    fn retry(
         &self,
-        route: Option<Route>,
+        method: Method,
         request: Request,
-        json: serde_json::Value,
+        body: &Body,
+        retry_policy: &RetryPolicy,
     ) -> Result<Response> {
-        let response = if let Some(route) = route {
-            let mutex = self.lock(route);
-            let mut rate_limit = mutex.lock();
-            if rate_limit.remaining == 0 {
-                if let Ok(delay) = rate_limit.reset.duration_since(SystemTime::now()) {
-                    thread::sleep(delay);
-                }
-            }
-            let response = loop {
-                match request.clone().send_json(json.clone()) {
-                    Ok(response) => break response,
-                    Err(ureq::Error::Status(429, response)) => {
-                        let too_many_requests = response.into_json::<TooManyRequests>()?;
-                        thread::sleep(Duration::from_secs_f64(too_many_requests.retry_after));
+        let mut attempt = 0;
+        loop {
+            self.wait_global();
+            let request = request.clone();
+            let result = match body {
+                Body::Empty => request.call(),
+                Body::Json(json) => request.send_json(json),
+                Body::Multipart { boundary, data } => request
+                    .set(
+                        "Content-Type",
+                        &format!("multipart/form-data; boundary={}", boundary),
+                    )
+                    .send_bytes(data),
+            };
+            match result {
+                Ok(response) => return Ok(response),
+                Err(ureq::Error::Status(429, response)) => {
+                    let global = rate_limit::is_global(|name| response.header(name));
+                    let too_many_requests = response.into_json::<TooManyRequests>()?;
+                    let retry_after = too_many_requests.retry_after()?;
+                    if global || too_many_requests.global {
+                        self.global.lock().block(retry_after);
+                    } else {
+                        thread::sleep(retry_after);
                     }
-                    Err(err) => return Err(err.into()),
                 }
-            };
-            let limit = response
-                .header("x-ratelimit-limit")
-                .map(|x| x.parse::<u64>().unwrap())
-                .unwrap_or(rate_limit.limit);
-            let remaining = response
-                .header("x-ratelimit-remaining")
-                .map(|x| x.parse::<u64>().unwrap())
-                .unwrap_or(rate_limit.remaining);
-            let reset = response
-                .header("x-ratelimit-reset")
-                .map(|x| std::time::UNIX_EPOCH + Duration::from_secs_f64(x.parse().unwrap()))
-                .unwrap_or(rate_limit.reset);
-            *rate_limit = RateLimit {
-                limit,
-                remaining,
-                reset,
-            };
-            response
-        } else {
-            loop {
-                match request.clone().send_json(json.clone()) {
-                    Ok(response) => break response,
-                    Err(ureq::Error::Status(429, response)) => {
-                        let too_many_requests = response.into_json::<TooManyRequests>()?;
-                        thread::sleep(Duration::from_secs_f64(too_many_requests.retry_after));
+                Err(err) => {
+                    attempt += 1;
+                    if !retry_policy.should_retry(method, attempt, Failure::from(&err)) {
+                        return Err(match err {
+                            ureq::Error::Status(status, response) => {
+                                Error::from_response(status, response)
+                            }
+                            err => err.into(),
+                        });
                     }
-                    Err(err) => return Err(err.into()),
+                    thread::sleep(retry_policy.delay(attempt));
                 }
             }
-        };
-        Ok(response)
+        }
     }
 }
-
-#[derive(Debug)]
-struct RateLimit {
-    limit: u64,
-    remaining: u64,
-    reset: SystemTime,
-}
-
-#[derive(Debug, Deserialize)]
-struct TooManyRequests {
-    retry_after: f64,
-}
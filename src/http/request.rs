@@ -0,0 +1,80 @@
+use std::io::Read;
+
+use multipart::client::lazy::PreparedFields;
+
+use super::Route;
+use crate::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+}
+
+impl Method {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Get => "GET",
+            Self::Post => "POST",
+            Self::Put => "PUT",
+            Self::Patch => "PATCH",
+            Self::Delete => "DELETE",
+        }
+    }
+
+    pub const fn is_idempotent(self) -> bool {
+        matches!(self, Self::Get | Self::Put | Self::Delete)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Body {
+    Empty,
+    Json(serde_json::Value),
+    Multipart { boundary: String, data: Vec<u8> },
+}
+
+impl Body {
+    pub fn multipart(mut fields: PreparedFields) -> Result<Self> {
+        let boundary = fields.boundary().to_string();
+        let mut data = Vec::new();
+        fields.read_to_end(&mut data)?;
+        Ok(Self::Multipart { boundary, data })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub(crate) route: Route,
+    pub(crate) path: String,
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) body: Body,
+}
+
+impl Request {
+    pub fn new<T: ToString>(route: Route, path: T) -> Self {
+        Self {
+            route,
+            path: path.to_string(),
+            headers: Vec::new(),
+            body: Body::Empty,
+        }
+    }
+
+    pub fn header<K: ToString, V: ToString>(mut self, name: K, value: V) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn body(mut self, body: Body) -> Self {
+        self.body = body;
+        self
+    }
+
+    pub fn json(self, json: serde_json::Value) -> Self {
+        self.body(Body::Json(json))
+    }
+}
@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use rand::Rng;
+use ureq::ErrorKind;
+
+use super::Method;
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    statuses: Vec<u16>,
+    retry_transport: bool,
+    retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+            statuses: vec![500, 502, 503, 504],
+            retry_transport: true,
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn none() -> Self {
+        Self::default().max_attempts(1)
+    }
+
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    pub fn statuses(mut self, statuses: Vec<u16>) -> Self {
+        self.statuses = statuses;
+        self
+    }
+
+    pub fn retry_transport(mut self, retry_transport: bool) -> Self {
+        self.retry_transport = retry_transport;
+        self
+    }
+
+    // POST and PATCH requests may have been applied by Discord even when the
+    // response is lost, so they are only retried when explicitly allowed.
+    pub fn retry_non_idempotent(mut self, retry_non_idempotent: bool) -> Self {
+        self.retry_non_idempotent = retry_non_idempotent;
+        self
+    }
+
+    pub(crate) fn should_retry(&self, method: Method, attempt: u32, failure: Failure) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+        let idempotent = self.retry_non_idempotent || method.is_idempotent();
+        match failure {
+            Failure::Status(status) => idempotent && self.statuses.contains(&status),
+            Failure::Unsent => self.retry_transport,
+            Failure::Interrupted => self.retry_transport && idempotent,
+            Failure::Other => false,
+        }
+    }
+
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay);
+        delay.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Failure {
+    Status(u16),
+    // The request never reached the server.
+    Unsent,
+    // The connection failed while the request was in flight.
+    Interrupted,
+    Other,
+}
+
+impl From<&ureq::Error> for Failure {
+    fn from(error: &ureq::Error) -> Self {
+        match error {
+            ureq::Error::Status(status, _) => Self::Status(*status),
+            ureq::Error::Transport(transport) => match transport.kind() {
+                ErrorKind::Dns | ErrorKind::ConnectionFailed | ErrorKind::ProxyConnect => {
+                    Self::Unsent
+                }
+                ErrorKind::Io | ErrorKind::BadStatus | ErrorKind::BadHeader => Self::Interrupted,
+                _ => Self::Other,
+            },
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl From<&reqwest::Error> for Failure {
+    fn from(error: &reqwest::Error) -> Self {
+        if error.is_connect() {
+            Self::Unsent
+        } else if error.is_timeout() || error.is_request() || error.is_body() {
+            Self::Interrupted
+        } else {
+            Self::Other
+        }
+    }
+}
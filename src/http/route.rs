@@ -1,12 +1,47 @@
 use std::hash::Hash;
 
-use crate::model::id::{ChannelId, GuildId, MessageId, UserId, WebhookId};
+use super::Method;
+use crate::model::id::{ChannelId, GuildId, WebhookId};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum Route {
+pub struct Route {
+    pub method: Method,
+    pub path: &'static str,
+    pub major: MajorParameter,
+}
+
+impl Route {
+    pub fn new<T: Into<MajorParameter>>(method: Method, path: &'static str, major: T) -> Self {
+        Self {
+            method,
+            path,
+            major: major.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MajorParameter {
+    None,
     Channel(ChannelId),
-    ChannelMessage(ChannelId, MessageId),
     Guild(GuildId),
-    GuildMember(GuildId, UserId),
     Webhook(WebhookId),
 }
+
+impl From<ChannelId> for MajorParameter {
+    fn from(channel_id: ChannelId) -> Self {
+        Self::Channel(channel_id)
+    }
+}
+
+impl From<GuildId> for MajorParameter {
+    fn from(guild_id: GuildId) -> Self {
+        Self::Guild(guild_id)
+    }
+}
+
+impl From<WebhookId> for MajorParameter {
+    fn from(webhook_id: WebhookId) -> Self {
+        Self::Webhook(webhook_id)
+    }
+}
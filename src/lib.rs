@@ -2,12 +2,15 @@
 mod macros;
 
 pub mod client;
+mod config;
 pub mod consts;
 mod error;
 pub mod gateway;
 pub mod http;
 pub mod model;
+mod token;
 
-pub use error::Error;
+pub use config::Config;
+pub use error::{Error, JsonErrorCode};
 
 pub type Result<T = ()> = std::result::Result<T, Error>;
@@ -1,17 +1,12 @@
 macro_rules! api {
-    ($e:expr $(,)?) => {
-        format!(
-            "{}/v{}{}",
-            crate::consts::API_URL,
-            crate::consts::API_VERSION,
-            $e,
-        )
+    ($config:expr, $e:expr $(,)?) => {
+        format!("{}/v{}{}", $config.api_url, $config.api_version, $e)
     };
-    ($f:expr, $($e:expr),+ $(,)?) => {
+    ($config:expr, $f:expr, $($e:expr),+ $(,)?) => {
         format!(
             "{}/v{}{}",
-            crate::consts::API_URL,
-            crate::consts::API_VERSION,
+            $config.api_url,
+            $config.api_version,
             format!($f, $($e,)*),
         )
     };
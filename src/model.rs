@@ -1,16 +1,28 @@
 mod activity;
 mod channel;
+mod emoji;
+mod gateway;
+mod guild;
 pub mod id;
+mod integration;
+mod interaction;
 mod member;
 mod message;
 mod role;
 mod user;
+mod voice;
 mod webhook;
 
 pub use activity::Activity;
-pub use channel::Channel;
+pub use channel::{Channel, ChannelKind, ThreadMember, ThreadMetadata};
+pub use emoji::Emoji;
+pub use gateway::{GatewayBot, SessionStartLimit};
+pub use guild::{Guild, UnavailableGuild};
+pub use integration::{Integration, IntegrationAccount};
+pub use interaction::{Interaction, InteractionKind};
 pub use member::{Member, PartialMember};
-pub use message::Message;
+pub use message::{Message, MessageKind};
 pub use role::Role;
 pub use user::User;
+pub use voice::VoiceState;
 pub use webhook::{Webhook, WebhookKind};
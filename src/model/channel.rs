@@ -2,7 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use serde_repr::Deserialize_repr;
 
-use super::id::{ChannelId, GuildId, MessageId, UserId};
+use super::id::{ApplicationId, ChannelId, GuildId, MessageId, UserId};
 use super::User;
 use crate::client::Context;
 use crate::Result;
@@ -28,16 +28,16 @@ pub struct Channel {
     pub recipients: Vec<User>,
     pub icon: Option<String>,
     pub owner_id: Option<UserId>,
-    // application_id: Option<ApplicationId>,
-    // parent_id: Option<>,
+    pub application_id: Option<ApplicationId>,
+    pub parent_id: Option<ChannelId>,
     pub last_pin_timestamp: Option<DateTime<Utc>>,
     pub rtc_region: Option<String>,
     pub video_quality_mode: Option<u64>,
-    pub message_count: Option<u8>,
-    pub member_count: Option<u8>,
-    // thread_metadata: Option<>,
-    // member: Option<>,
-    // default_auto_archive_duration: ,
+    pub message_count: Option<u64>,
+    pub member_count: Option<u64>,
+    pub thread_metadata: Option<ThreadMetadata>,
+    pub member: Option<ThreadMember>,
+    pub default_auto_archive_duration: Option<u64>,
     pub permissions: Option<String>,
 }
 
@@ -59,7 +59,7 @@ impl Channel {
             if let Some(channel) = channels.into_iter().find(|x| {
                 x.name
                     .as_ref()
-                    .map_or(false, |y| y.eq_ignore_ascii_case(text))
+                    .is_some_and(|y| y.eq_ignore_ascii_case(text))
             }) {
                 return Ok(Some(channel));
             }
@@ -68,6 +68,26 @@ impl Channel {
     }
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct ThreadMetadata {
+    pub archived: bool,
+    pub auto_archive_duration: u64,
+    pub archive_timestamp: DateTime<Utc>,
+    pub locked: bool,
+    pub invitable: Option<bool>,
+    pub create_timestamp: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ThreadMember {
+    // Both ids are left out in the thread member of a `Channel` and in
+    // `GUILD_CREATE`.
+    pub id: Option<ChannelId>,
+    pub user_id: Option<UserId>,
+    pub join_timestamp: DateTime<Utc>,
+    pub flags: u64,
+}
+
 #[derive(Debug, Clone, Copy, Deserialize_repr)]
 #[repr(u8)]
 pub enum ChannelKind {
@@ -0,0 +1,23 @@
+use serde::Deserialize;
+
+use super::id::{EmojiId, RoleId};
+use super::User;
+
+// Reactions only carry `id`, `name` and `animated`, and `id` is `None` for
+// unicode emojis.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Emoji {
+    pub id: Option<EmojiId>,
+    pub name: Option<String>,
+    #[serde(default)]
+    pub roles: Vec<RoleId>,
+    pub user: Option<User>,
+    #[serde(default)]
+    pub require_colons: bool,
+    #[serde(default)]
+    pub managed: bool,
+    #[serde(default)]
+    pub animated: bool,
+    #[serde(default)]
+    pub available: bool,
+}
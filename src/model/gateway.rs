@@ -0,0 +1,23 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Deserializer};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GatewayBot {
+    pub url: String,
+    pub shards: u64,
+    pub session_start_limit: SessionStartLimit,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SessionStartLimit {
+    pub total: u64,
+    pub remaining: u64,
+    #[serde(deserialize_with = "deserialize_millis")]
+    pub reset_after: Duration,
+    pub max_concurrency: u64,
+}
+
+fn deserialize_millis<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    u64::deserialize(deserializer).map(Duration::from_millis)
+}
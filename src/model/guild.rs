@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use super::id::{ApplicationId, ChannelId, GuildId, UserId};
+use super::{Channel, Emoji, Member, Role, VoiceState};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Guild {
+    pub id: GuildId,
+    pub name: String,
+    pub icon: Option<String>,
+    pub splash: Option<String>,
+    pub discovery_splash: Option<String>,
+    pub owner_id: UserId,
+    pub afk_channel_id: Option<ChannelId>,
+    pub afk_timeout: u64,
+    pub verification_level: u64,
+    pub default_message_notifications: u64,
+    pub explicit_content_filter: u64,
+    pub roles: Vec<Role>,
+    pub emojis: Vec<Emoji>,
+    pub features: Vec<String>,
+    pub mfa_level: u64,
+    pub application_id: Option<ApplicationId>,
+    pub system_channel_id: Option<ChannelId>,
+    pub rules_channel_id: Option<ChannelId>,
+    pub max_members: Option<u64>,
+    pub vanity_url_code: Option<String>,
+    pub description: Option<String>,
+    pub banner: Option<String>,
+    pub premium_tier: u64,
+    pub premium_subscription_count: Option<u64>,
+    pub preferred_locale: String,
+    #[serde(default)]
+    pub nsfw_level: u64,
+    // Only sent in `GUILD_CREATE`.
+    pub joined_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub large: bool,
+    pub member_count: Option<u64>,
+    #[serde(default)]
+    pub voice_states: Vec<VoiceState>,
+    #[serde(default)]
+    pub members: Vec<Member>,
+    #[serde(default)]
+    pub channels: Vec<Channel>,
+    #[serde(default)]
+    pub threads: Vec<Channel>,
+    // #[serde(default)]
+    // pub presences: Vec<PresenceUpdate>,
+    // #[serde(default)]
+    // pub stage_instances: Vec<StageInstance>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UnavailableGuild {
+    pub id: GuildId,
+    // `false` in `GUILD_DELETE` when the bot was removed from the guild.
+    #[serde(default)]
+    pub unavailable: bool,
+}
@@ -41,8 +41,12 @@ macro_rules! id_u64 {
 }
 
 id_u64! {
+    ApplicationId,
     ChannelId,
+    EmojiId,
     GuildId,
+    IntegrationId,
+    InteractionId,
     MessageId,
     RoleId,
     UserId,
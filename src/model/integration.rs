@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use super::id::{IntegrationId, RoleId};
+use super::User;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Integration {
+    pub id: IntegrationId,
+    pub name: String,
+    // "twitch", "youtube", "discord" or "guild_subscription"
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub enabled: bool,
+    pub syncing: Option<bool>,
+    pub role_id: Option<RoleId>,
+    pub enable_emoticons: Option<bool>,
+    pub expire_behavior: Option<u64>,
+    pub expire_grace_period: Option<u64>,
+    pub user: Option<User>,
+    pub account: IntegrationAccount,
+    pub synced_at: Option<DateTime<Utc>>,
+    pub subscriber_count: Option<u64>,
+    pub revoked: Option<bool>,
+    // pub application: Option<IntegrationApplication>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IntegrationAccount {
+    pub id: String,
+    pub name: String,
+}
@@ -0,0 +1,36 @@
+use serde::Deserialize;
+use serde_repr::Deserialize_repr;
+
+use super::id::{ApplicationId, ChannelId, GuildId, InteractionId};
+use super::{Member, Message, User};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Interaction {
+    pub id: InteractionId,
+    pub application_id: ApplicationId,
+    #[serde(rename = "type")]
+    pub kind: InteractionKind,
+    // The shape depends on `kind` and on the command or component.
+    #[serde(default)]
+    pub data: serde_json::Value,
+    pub guild_id: Option<GuildId>,
+    pub channel_id: Option<ChannelId>,
+    // `member` is sent in guilds, `user` in direct messages.
+    pub member: Option<Member>,
+    pub user: Option<User>,
+    pub token: String,
+    pub version: u64,
+    pub message: Option<Box<Message>>,
+    pub locale: Option<String>,
+    pub guild_locale: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize_repr)]
+#[repr(u8)]
+pub enum InteractionKind {
+    Ping = 1,
+    ApplicationCommand = 2,
+    MessageComponent = 3,
+    ApplicationCommandAutocomplete = 4,
+    ModalSubmit = 5,
+}
@@ -54,7 +54,7 @@ impl Member {
             x.user.username.eq_ignore_ascii_case(text)
                 || x.nick
                     .as_ref()
-                    .map_or(false, |y| y.eq_ignore_ascii_case(text))
+                    .is_some_and(|y| y.eq_ignore_ascii_case(text))
         }) {
             return Ok(Some(member));
         }
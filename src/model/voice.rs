@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use super::id::{ChannelId, GuildId, UserId};
+use super::Member;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct VoiceState {
+    pub guild_id: Option<GuildId>,
+    pub channel_id: Option<ChannelId>,
+    pub user_id: UserId,
+    pub member: Option<Member>,
+    pub session_id: String,
+    pub deaf: bool,
+    pub mute: bool,
+    pub self_deaf: bool,
+    pub self_mute: bool,
+    #[serde(default)]
+    pub self_stream: bool,
+    pub self_video: bool,
+    pub suppress: bool,
+    pub request_to_speak_timestamp: Option<DateTime<Utc>>,
+}
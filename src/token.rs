@@ -0,0 +1,28 @@
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+// Keeps the token out of `Debug` output so that clients and handlers can be
+// logged safely.
+#[derive(Clone)]
+pub(crate) struct Token(Arc<str>);
+
+impl Deref for Token {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        "[redacted]".fmt(f)
+    }
+}
+
+impl From<Arc<str>> for Token {
+    fn from(token: Arc<str>) -> Self {
+        Self(token)
+    }
+}
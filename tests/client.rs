@@ -0,0 +1,200 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::Receiver;
+use harmony::client::{ClientBuilder, Context, DispatchOrdering, DispatchPolicy, EventHandler};
+use harmony::model::Message;
+use harmony::Config;
+use parking_lot::Mutex;
+use tungstenite::Message as WsMessage;
+
+const READY: &str = r#"{"op":0,"s":1,"t":"READY","d":{"v":10,"user":{"id":"1","username":"Nelly","discriminator":"0001","avatar":null},"guilds":[],"session_id":"session"}}"#;
+
+fn message_create(seq: u64, id: u64, guild_id: u64, channel_id: u64) -> String {
+    format!(
+        r#"{{"op":0,"s":{},"t":"MESSAGE_CREATE","d":{{"id":"{}","guild_id":"{}","channel_id":"{}","author":{{"id":"1","username":"Nelly","discriminator":"0001","avatar":null}},"content":"","timestamp":"2021-01-01T00:00:00+00:00","edited_timestamp":null,"tts":false,"mention_everyone":false,"mentions":[],"mention_roles":[],"pinned":false,"type":0}}}}"#,
+        seq, id, guild_id, channel_id
+    )
+}
+
+// Serves `/gateway/bot` and a gateway that sends `events` after the identify.
+// Returns the config to reach them, and the close codes the gateway receives.
+fn discord(events: Vec<String>) -> (Config, Receiver<Option<u16>>) {
+    let gateway = TcpListener::bind("127.0.0.1:0").unwrap();
+    let gateway_port = gateway.local_addr().unwrap().port();
+    let api = TcpListener::bind("127.0.0.1:0").unwrap();
+    let api_port = api.local_addr().unwrap().port();
+    thread::spawn(move || {
+        for stream in api.incoming() {
+            let mut reader = BufReader::new(stream.unwrap());
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+            }
+            let body = format!(
+                r#"{{"url":"ws://127.0.0.1:{}","shards":1,"session_start_limit":{{"total":1000,"remaining":1000,"reset_after":1000,"max_concurrency":1}}}}"#,
+                gateway_port
+            );
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            reader.into_inner().write_all(response.as_bytes()).unwrap();
+        }
+    });
+    let (close_sender, close_receiver) = crossbeam_channel::unbounded();
+    thread::spawn(move || {
+        for stream in gateway.incoming() {
+            let mut socket = tungstenite::accept(stream.unwrap()).unwrap();
+            let events = events.clone();
+            let close_sender = close_sender.clone();
+            thread::spawn(move || {
+                let hello = r#"{"op":10,"d":{"heartbeat_interval":45000}}"#;
+                socket.write_message(WsMessage::Text(hello.into())).unwrap();
+                while let Ok(message) = socket.read_message() {
+                    match message {
+                        WsMessage::Text(text) if text.contains(r#""op":2"#) => {
+                            socket.write_message(WsMessage::Text(READY.into())).unwrap();
+                            for event in &events {
+                                socket
+                                    .write_message(WsMessage::Text(event.clone()))
+                                    .unwrap();
+                            }
+                        }
+                        WsMessage::Text(text) if text.contains(r#""op":1"#) => {
+                            let ack = r#"{"op":11}"#;
+                            socket.write_message(WsMessage::Text(ack.into())).unwrap();
+                        }
+                        WsMessage::Close(frame) => {
+                            close_sender.send(frame.map(|frame| frame.code.into())).ok();
+                        }
+                        _ => (),
+                    }
+                }
+            });
+        }
+    });
+    let config = Config::new()
+        .api_url(format!("http://127.0.0.1:{}", api_port))
+        .tls(false);
+    (config, close_receiver)
+}
+
+#[test]
+fn shutdown_drains_queue() {
+    let events = (0..5).map(|i| message_create(i + 2, i, 7, 5)).collect();
+    let (config, closes) = discord(events);
+    let handled = Arc::new(Mutex::new(Vec::new()));
+    let result = ClientBuilder::new()
+        .with_bot_token("token")
+        .config(config)
+        .on_message_create({
+            let handled = handled.clone();
+            move |ctx: Context, message: Message| {
+                if message.id.0 == 0 {
+                    // Gives the shard time to queue the other messages.
+                    thread::sleep(Duration::from_millis(300));
+                    ctx.shutdown_handle().shutdown();
+                }
+                handled.lock().push(message.id.0);
+            }
+        })
+        .build()
+        .run();
+    assert!(result.is_ok());
+    assert_eq!(*handled.lock(), vec![0, 1, 2, 3, 4]);
+    assert_eq!(closes.recv_timeout(Duration::from_secs(5)), Ok(Some(1000)));
+}
+
+#[test]
+fn handler_panic() {
+    let events = (0..5).map(|i| message_create(i + 2, i, 7, 5)).collect();
+    let (config, _closes) = discord(events);
+    let handled = Arc::new(Mutex::new(Vec::new()));
+    let result = ClientBuilder::new()
+        .with_bot_token("token")
+        .config(config)
+        .on_message_create({
+            let handled = handled.clone();
+            move |ctx: Context, message: Message| {
+                if message.id.0 == 0 {
+                    panic!("handler panic");
+                }
+                let mut handled = handled.lock();
+                handled.push(message.id.0);
+                if handled.len() == 4 {
+                    ctx.shutdown_handle().shutdown();
+                }
+            }
+        })
+        .build()
+        .run();
+    assert!(result.is_ok());
+    assert_eq!(*handled.lock(), vec![1, 2, 3, 4]);
+}
+
+// Handlers finish faster the later they start, so events overtake each other
+// unless the ordering holds them back.
+struct Recorder {
+    handled: Arc<Mutex<Vec<u64>>>,
+}
+
+impl EventHandler for Recorder {
+    fn on_message_create(&self, ctx: Context, message: Message) {
+        thread::sleep(Duration::from_millis(5 * (10 - message.id.0)));
+        let mut handled = self.handled.lock();
+        handled.push(message.id.0);
+        if handled.len() == 10 {
+            ctx.shutdown_handle().shutdown();
+        }
+    }
+}
+
+// Sends messages alternating between two keys and checks the order within
+// each key.
+fn ordered(ordering: DispatchOrdering, key: fn(u64) -> (u64, u64)) {
+    let events = (0..10)
+        .map(|i| {
+            let (guild_id, channel_id) = key(i);
+            message_create(i + 2, i, guild_id, channel_id)
+        })
+        .collect();
+    let (config, _closes) = discord(events);
+    let handled = Arc::new(Mutex::new(Vec::new()));
+    let result = ClientBuilder::new()
+        .with_bot_token("token")
+        .config(config)
+        .dispatch_policy(DispatchPolicy::new().workers(4).ordering(ordering))
+        .event_handler(Recorder {
+            handled: handled.clone(),
+        })
+        .build()
+        .run();
+    assert!(result.is_ok());
+    let handled = handled.lock();
+    for parity in 0..2 {
+        let ids = handled
+            .iter()
+            .copied()
+            .filter(|id| id % 2 == parity)
+            .collect::<Vec<_>>();
+        assert!(ids.windows(2).all(|ids| ids[0] < ids[1]), "{:?}", handled);
+    }
+}
+
+#[test]
+fn per_guild_ordering() {
+    ordered(DispatchOrdering::PerGuild, |i| (10 + i % 2, 5 + i % 3));
+}
+
+#[test]
+fn per_channel_ordering() {
+    ordered(DispatchOrdering::PerChannel, |i| (7, 5 + i % 2));
+}
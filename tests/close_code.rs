@@ -0,0 +1,32 @@
+use harmony::gateway::CloseCode;
+
+#[test]
+fn reconnect_and_resume() {
+    // (code, can_reconnect, can_resume)
+    let table = [
+        (4000, true, true),
+        (4001, true, true),
+        (4002, true, true),
+        (4003, true, false),
+        (4004, false, false),
+        (4005, true, true),
+        (4006, true, true),
+        (4007, true, false),
+        (4008, true, true),
+        (4009, true, false),
+        (4010, false, false),
+        (4011, false, false),
+        (4012, false, false),
+        (4013, false, false),
+        (4014, false, false),
+        (1001, true, true),
+    ];
+    for (code, can_reconnect, can_resume) in table {
+        let close_code = CloseCode::from(code);
+        assert_eq!(u16::from(close_code), code);
+        assert_eq!(close_code.can_reconnect(), can_reconnect, "{}", code);
+        assert_eq!(close_code.can_resume(), can_resume, "{}", code);
+    }
+    assert_eq!(CloseCode::from(4006), CloseCode::Other(4006));
+    assert_eq!(CloseCode::from(1001), CloseCode::Other(1001));
+}
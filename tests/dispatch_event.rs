@@ -0,0 +1,909 @@
+use harmony::gateway::{DispatchEvent, DispatchEventKind, Status};
+use harmony::model::id::{
+    ApplicationId, ChannelId, EmojiId, GuildId, IntegrationId, MessageId, RoleId, UserId,
+};
+use harmony::model::InteractionKind;
+use serde_json::{json, Value};
+
+fn dispatch(name: &str, d: Value) -> DispatchEventKind {
+    let event: DispatchEvent =
+        serde_json::from_value(json!({ "op": 0, "s": 42, "t": name, "d": d })).unwrap();
+    assert_eq!(event.sequence_number, 42);
+    event.kind
+}
+
+fn user() -> Value {
+    json!({
+        "id": "80351110224678912",
+        "username": "Nelly",
+        "discriminator": "1337",
+        "avatar": "8342729096ea3675442027381ff50dfe",
+        "public_flags": 64
+    })
+}
+
+fn member() -> Value {
+    json!({
+        "user": user(),
+        "nick": "NOT API SUPPORT",
+        "avatar": null,
+        "roles": ["41771983423143936"],
+        "joined_at": "2015-04-26T06:26:56.936000+00:00",
+        "deaf": false,
+        "mute": false
+    })
+}
+
+fn role() -> Value {
+    json!({
+        "id": "41771983423143936",
+        "name": "WE DEM BOYZZ!!!!!!",
+        "color": 3447003,
+        "hoist": true,
+        "position": 1,
+        "permissions": "66321471",
+        "managed": false,
+        "mentionable": false
+    })
+}
+
+fn channel() -> Value {
+    json!({
+        "id": "41771983423143937",
+        "guild_id": "41771983423143936",
+        "name": "general",
+        "type": 0,
+        "position": 6,
+        "permission_overwrites": [],
+        "rate_limit_per_user": 2,
+        "nsfw": true,
+        "topic": "24/7 chat about how to gank Mike #2",
+        "last_message_id": "155117677105512449",
+        "parent_id": "399942396007890945"
+    })
+}
+
+fn thread() -> Value {
+    json!({
+        "id": "41771983423143937",
+        "guild_id": "41771983423143936",
+        "parent_id": "41771983423143936",
+        "owner_id": "80351110224678912",
+        "name": "don't buy dota-2",
+        "type": 11,
+        "last_message_id": "155117677105512449",
+        "message_count": 1,
+        "member_count": 5,
+        "rate_limit_per_user": 2,
+        "thread_metadata": {
+            "archived": false,
+            "auto_archive_duration": 1440,
+            "archive_timestamp": "2021-04-12T23:40:39.855793+00:00",
+            "locked": false
+        },
+        "total_message_sent": 1
+    })
+}
+
+fn guild() -> Value {
+    json!({
+        "id": "197038439483310086",
+        "name": "Discord Testers",
+        "icon": "f64c482b807da4f539cff778d174971c",
+        "splash": null,
+        "discovery_splash": null,
+        "owner_id": "73193882359173120",
+        "afk_channel_id": null,
+        "afk_timeout": 300,
+        "verification_level": 3,
+        "default_message_notifications": 1,
+        "explicit_content_filter": 2,
+        "roles": [role()],
+        "emojis": [],
+        "features": ["ANIMATED_ICON", "VERIFIED", "NEWS"],
+        "mfa_level": 1,
+        "application_id": null,
+        "system_channel_id": null,
+        "rules_channel_id": "441688182833020939",
+        "max_members": 250000,
+        "vanity_url_code": "discord-testers",
+        "description": "The official place to report Discord Bugs!",
+        "banner": "9b6439a7de04f1d26af92f84ac9e1e4a",
+        "premium_tier": 3,
+        "premium_subscription_count": 33,
+        "preferred_locale": "en-US",
+        "nsfw_level": 0
+    })
+}
+
+fn emoji() -> Value {
+    json!({ "id": "41771983429993937", "name": "LUL", "animated": true })
+}
+
+#[test]
+fn ready() {
+    let ready = dispatch(
+        "READY",
+        json!({
+            "v": 10,
+            "user": user(),
+            "guilds": [{ "id": "197038439483310086", "unavailable": true }],
+            "session_id": "abc",
+            "resume_gateway_url": "wss://gateway-us-east1-b.discord.gg",
+            "shard": [0, 1],
+            "application": { "id": "1", "flags": 0 }
+        }),
+    )
+    .into_ready()
+    .unwrap();
+    assert_eq!(ready.guilds.len(), 1);
+    assert!(ready.guilds[0].unavailable);
+}
+
+#[test]
+fn resumed() {
+    assert!(matches!(
+        dispatch("RESUMED", json!({ "_trace": [] })),
+        DispatchEventKind::Resumed
+    ));
+}
+
+#[test]
+fn channel_create() {
+    match dispatch("CHANNEL_CREATE", channel()) {
+        DispatchEventKind::ChannelCreate(channel) => {
+            assert_eq!(channel.id, ChannelId(41771983423143937));
+            assert_eq!(channel.parent_id, Some(ChannelId(399942396007890945)));
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn channel_update() {
+    let event = dispatch("CHANNEL_UPDATE", channel());
+    assert_eq!(event.guild_id(), Some(GuildId(41771983423143936)));
+    assert!(matches!(event, DispatchEventKind::ChannelUpdate(_)));
+}
+
+#[test]
+fn channel_delete() {
+    assert!(matches!(
+        dispatch("CHANNEL_DELETE", channel()),
+        DispatchEventKind::ChannelDelete(_)
+    ));
+}
+
+#[test]
+fn channel_pins_update() {
+    match dispatch(
+        "CHANNEL_PINS_UPDATE",
+        json!({
+            "guild_id": "41771983423143936",
+            "channel_id": "41771983423143937",
+            "last_pin_timestamp": "2021-04-12T23:40:39.855793+00:00"
+        }),
+    ) {
+        DispatchEventKind::ChannelPinsUpdate(event) => {
+            assert_eq!(event.channel_id, ChannelId(41771983423143937));
+            assert!(event.last_pin_timestamp.is_some());
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn thread_create() {
+    match dispatch("THREAD_CREATE", thread()) {
+        DispatchEventKind::ThreadCreate(thread) => {
+            assert_eq!(thread.message_count, Some(1));
+            assert_eq!(thread.member_count, Some(5));
+            assert_eq!(thread.thread_metadata.unwrap().auto_archive_duration, 1440);
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn thread_update() {
+    assert!(matches!(
+        dispatch("THREAD_UPDATE", thread()),
+        DispatchEventKind::ThreadUpdate(_)
+    ));
+}
+
+#[test]
+fn thread_delete() {
+    match dispatch(
+        "THREAD_DELETE",
+        json!({
+            "id": "41771983423143937",
+            "guild_id": "41771983423143936",
+            "parent_id": "41771983423143936",
+            "type": 11
+        }),
+    ) {
+        DispatchEventKind::ThreadDelete(event) => {
+            assert_eq!(event.id, ChannelId(41771983423143937))
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn thread_list_sync() {
+    match dispatch(
+        "THREAD_LIST_SYNC",
+        json!({
+            "guild_id": "41771983423143936",
+            "threads": [thread()],
+            "members": [{
+                "id": "41771983423143937",
+                "user_id": "80351110224678912",
+                "join_timestamp": "2021-04-12T23:40:39.855793+00:00",
+                "flags": 1
+            }]
+        }),
+    ) {
+        DispatchEventKind::ThreadListSync(event) => {
+            assert!(event.channel_ids.is_empty());
+            assert_eq!(event.threads.len(), 1);
+            assert_eq!(event.members[0].user_id, Some(UserId(80351110224678912)));
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn thread_member_update() {
+    match dispatch(
+        "THREAD_MEMBER_UPDATE",
+        json!({
+            "id": "41771983423143937",
+            "user_id": "80351110224678912",
+            "join_timestamp": "2021-04-12T23:40:39.855793+00:00",
+            "flags": 1,
+            "guild_id": "41771983423143936"
+        }),
+    ) {
+        DispatchEventKind::ThreadMemberUpdate(event) => {
+            assert_eq!(event.guild_id, GuildId(41771983423143936));
+            assert_eq!(event.member.id, Some(ChannelId(41771983423143937)));
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn thread_members_update() {
+    match dispatch(
+        "THREAD_MEMBERS_UPDATE",
+        json!({
+            "id": "41771983423143937",
+            "guild_id": "41771983423143936",
+            "member_count": 4,
+            "removed_member_ids": ["80351110224678912"]
+        }),
+    ) {
+        DispatchEventKind::ThreadMembersUpdate(event) => {
+            assert_eq!(event.member_count, 4);
+            assert!(event.added_members.is_empty());
+            assert_eq!(event.removed_member_ids, [UserId(80351110224678912)]);
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn guild_create() {
+    let mut d = guild();
+    d["joined_at"] = json!("2016-06-30T22:45:23.181000+00:00");
+    d["large"] = json!(true);
+    d["member_count"] = json!(2);
+    d["members"] = json!([member()]);
+    d["channels"] = json!([channel()]);
+    d["threads"] = json!([thread()]);
+    d["voice_states"] = json!([{
+        "channel_id": "157733188964188161",
+        "user_id": "80351110224678912",
+        "session_id": "90326bd25d71d39b9ef95b299e3872ff",
+        "deaf": false,
+        "mute": false,
+        "self_deaf": false,
+        "self_mute": true,
+        "self_video": false,
+        "suppress": false,
+        "request_to_speak_timestamp": null
+    }]);
+    d["presences"] = json!([]);
+    match dispatch("GUILD_CREATE", d) {
+        DispatchEventKind::GuildCreate(guild) => {
+            assert_eq!(guild.id, GuildId(197038439483310086));
+            assert!(guild.large);
+            assert_eq!(guild.members.len(), 1);
+            assert_eq!(guild.channels.len(), 1);
+            assert_eq!(guild.threads.len(), 1);
+            assert!(guild.voice_states[0].self_mute);
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn guild_update() {
+    let event = dispatch("GUILD_UPDATE", guild());
+    assert_eq!(event.guild_id(), Some(GuildId(197038439483310086)));
+    match event {
+        DispatchEventKind::GuildUpdate(guild) => {
+            assert_eq!(guild.roles[0].id, RoleId(41771983423143936));
+            assert!(guild.joined_at.is_none());
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn guild_delete() {
+    match dispatch("GUILD_DELETE", json!({ "id": "197038439483310086" })) {
+        DispatchEventKind::GuildDelete(guild) => assert!(!guild.unavailable),
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn guild_ban_add() {
+    match dispatch(
+        "GUILD_BAN_ADD",
+        json!({ "guild_id": "197038439483310086", "user": user() }),
+    ) {
+        DispatchEventKind::GuildBanAdd(event) => {
+            assert_eq!(event.user.id, UserId(80351110224678912))
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn guild_ban_remove() {
+    assert!(matches!(
+        dispatch(
+            "GUILD_BAN_REMOVE",
+            json!({ "guild_id": "197038439483310086", "user": user() }),
+        ),
+        DispatchEventKind::GuildBanRemove(_)
+    ));
+}
+
+#[test]
+fn guild_emojis_update() {
+    match dispatch(
+        "GUILD_EMOJIS_UPDATE",
+        json!({
+            "guild_id": "197038439483310086",
+            "emojis": [{
+                "id": "41771983429993937",
+                "name": "LUL",
+                "roles": ["41771983429993000", "41771983429993111"],
+                "user": user(),
+                "require_colons": true,
+                "managed": false,
+                "animated": false
+            }]
+        }),
+    ) {
+        DispatchEventKind::GuildEmojisUpdate(event) => {
+            assert_eq!(event.emojis[0].id, Some(EmojiId(41771983429993937)));
+            assert_eq!(event.emojis[0].roles.len(), 2);
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn guild_integrations_update() {
+    assert!(matches!(
+        dispatch(
+            "GUILD_INTEGRATIONS_UPDATE",
+            json!({ "guild_id": "197038439483310086" })
+        ),
+        DispatchEventKind::GuildIntegrationsUpdate(_)
+    ));
+}
+
+#[test]
+fn guild_member_add() {
+    let mut d = member();
+    d["guild_id"] = json!("197038439483310086");
+    match dispatch("GUILD_MEMBER_ADD", d) {
+        DispatchEventKind::GuildMemberAdd(event) => {
+            assert_eq!(event.guild_id, GuildId(197038439483310086));
+            assert_eq!(event.member.nick.as_deref(), Some("NOT API SUPPORT"));
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn guild_member_remove() {
+    match dispatch(
+        "GUILD_MEMBER_REMOVE",
+        json!({ "guild_id": "197038439483310086", "user": user() }),
+    ) {
+        DispatchEventKind::GuildMemberRemove(event) => {
+            assert_eq!(event.user.username, "Nelly")
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn guild_member_update() {
+    match dispatch(
+        "GUILD_MEMBER_UPDATE",
+        json!({
+            "guild_id": "197038439483310086",
+            "roles": [],
+            "user": user(),
+            "nick": null,
+            "avatar": null,
+            "joined_at": null,
+            "premium_since": null,
+            "pending": false,
+            "communication_disabled_until": "2021-12-01T00:00:00+00:00"
+        }),
+    ) {
+        DispatchEventKind::GuildMemberUpdate(event) => {
+            assert!(event.joined_at.is_none());
+            assert!(event.deaf.is_none());
+            assert!(event.communication_disabled_until.is_some());
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn guild_members_chunk() {
+    match dispatch(
+        "GUILD_MEMBERS_CHUNK",
+        json!({
+            "guild_id": "197038439483310086",
+            "members": [member()],
+            "chunk_index": 0,
+            "chunk_count": 1,
+            "not_found": ["80351110224678913"],
+            "nonce": "members"
+        }),
+    ) {
+        DispatchEventKind::GuildMembersChunk(event) => {
+            assert_eq!(event.members.len(), 1);
+            assert_eq!(event.not_found, [UserId(80351110224678913)]);
+            assert_eq!(event.nonce.as_deref(), Some("members"));
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn guild_role_create() {
+    match dispatch(
+        "GUILD_ROLE_CREATE",
+        json!({ "guild_id": "197038439483310086", "role": role() }),
+    ) {
+        DispatchEventKind::GuildRoleCreate(event) => assert_eq!(event.role.color, 3447003),
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn guild_role_update() {
+    assert!(matches!(
+        dispatch(
+            "GUILD_ROLE_UPDATE",
+            json!({ "guild_id": "197038439483310086", "role": role() }),
+        ),
+        DispatchEventKind::GuildRoleUpdate(_)
+    ));
+}
+
+#[test]
+fn guild_role_delete() {
+    match dispatch(
+        "GUILD_ROLE_DELETE",
+        json!({ "guild_id": "197038439483310086", "role_id": "41771983423143936" }),
+    ) {
+        DispatchEventKind::GuildRoleDelete(event) => {
+            assert_eq!(event.role_id, RoleId(41771983423143936))
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn integration_create() {
+    match dispatch(
+        "INTEGRATION_CREATE",
+        json!({
+            "id": "33590653072239123",
+            "name": "A Name",
+            "type": "twitch",
+            "enabled": true,
+            "syncing": false,
+            "role_id": "41771983423143936",
+            "expire_behavior": 0,
+            "expire_grace_period": 1,
+            "user": user(),
+            "account": { "id": "1234567", "name": "twitchuser" },
+            "synced_at": "2019-08-22T00:00:00+00:00",
+            "guild_id": "197038439483310086"
+        }),
+    ) {
+        DispatchEventKind::IntegrationCreate(event) => {
+            assert_eq!(event.guild_id, GuildId(197038439483310086));
+            assert_eq!(event.integration.id, IntegrationId(33590653072239123));
+            assert_eq!(event.integration.account.name, "twitchuser");
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn integration_update() {
+    assert!(matches!(
+        dispatch(
+            "INTEGRATION_UPDATE",
+            json!({
+                "id": "33590653072239123",
+                "name": "Bot",
+                "type": "discord",
+                "enabled": true,
+                "account": { "id": "33590653072239123", "name": "Bot" },
+                "guild_id": "197038439483310086"
+            }),
+        ),
+        DispatchEventKind::IntegrationUpdate(_)
+    ));
+}
+
+#[test]
+fn integration_delete() {
+    match dispatch(
+        "INTEGRATION_DELETE",
+        json!({
+            "id": "33590653072239123",
+            "guild_id": "197038439483310086",
+            "application_id": "33590653072239123"
+        }),
+    ) {
+        DispatchEventKind::IntegrationDelete(event) => {
+            assert_eq!(event.application_id, Some(ApplicationId(33590653072239123)))
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn interaction_create() {
+    let event = dispatch(
+        "INTERACTION_CREATE",
+        json!({
+            "id": "786008729715212338",
+            "application_id": "775799577604522054",
+            "type": 2,
+            "data": { "id": "771825006014889984", "name": "blep", "type": 1 },
+            "guild_id": "290926798626357999",
+            "channel_id": "645027906669510667",
+            "member": member(),
+            "token": "A_UNIQUE_TOKEN",
+            "version": 1,
+            "locale": "en-US",
+            "guild_locale": "en-US"
+        }),
+    );
+    assert_eq!(event.guild_id(), Some(GuildId(290926798626357999)));
+    match event {
+        DispatchEventKind::InteractionCreate(interaction) => {
+            assert_eq!(interaction.kind, InteractionKind::ApplicationCommand);
+            assert_eq!(interaction.data["name"], "blep");
+            assert!(interaction.user.is_none());
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn invite_create() {
+    match dispatch(
+        "INVITE_CREATE",
+        json!({
+            "channel_id": "165176875973476352",
+            "code": "0vCdhLbwjZZTWZLD",
+            "created_at": "2016-03-31T19:15:39.954000+00:00",
+            "guild_id": "165176875973476352",
+            "inviter": user(),
+            "max_age": 86400,
+            "max_uses": 0,
+            "temporary": false,
+            "uses": 0
+        }),
+    ) {
+        DispatchEventKind::InviteCreate(invite) => {
+            assert_eq!(invite.code, "0vCdhLbwjZZTWZLD");
+            assert_eq!(invite.max_age, 86400);
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn invite_delete() {
+    match dispatch(
+        "INVITE_DELETE",
+        json!({ "channel_id": "165176875973476352", "code": "0vCdhLbwjZZTWZLD" }),
+    ) {
+        DispatchEventKind::InviteDelete(invite) => assert!(invite.guild_id.is_none()),
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn message_update() {
+    match dispatch(
+        "MESSAGE_UPDATE",
+        json!({
+            "id": "334385199974967042",
+            "channel_id": "290926798999357250",
+            "guild_id": "290926798626357999",
+            "content": "Supa Hot",
+            "edited_timestamp": "2017-07-11T17:27:07.299000+00:00"
+        }),
+    ) {
+        DispatchEventKind::MessageUpdate(message) => {
+            assert_eq!(message.id, MessageId(334385199974967042));
+            assert_eq!(message.content.as_deref(), Some("Supa Hot"));
+            assert!(message.author.is_none());
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn message_delete() {
+    match dispatch(
+        "MESSAGE_DELETE",
+        json!({
+            "id": "334385199974967042",
+            "channel_id": "290926798999357250",
+            "guild_id": "290926798626357999"
+        }),
+    ) {
+        DispatchEventKind::MessageDelete(event) => {
+            assert_eq!(event.id, MessageId(334385199974967042))
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn message_delete_bulk() {
+    match dispatch(
+        "MESSAGE_DELETE_BULK",
+        json!({
+            "ids": ["334385199974967042", "334385199974967043"],
+            "channel_id": "290926798999357250"
+        }),
+    ) {
+        DispatchEventKind::MessageDeleteBulk(event) => {
+            assert_eq!(event.ids.len(), 2);
+            assert!(event.guild_id.is_none());
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn message_reaction_add() {
+    match dispatch(
+        "MESSAGE_REACTION_ADD",
+        json!({
+            "user_id": "80351110224678912",
+            "channel_id": "290926798999357250",
+            "message_id": "334385199974967042",
+            "guild_id": "290926798626357999",
+            "member": member(),
+            "emoji": emoji()
+        }),
+    ) {
+        DispatchEventKind::MessageReactionAdd(event) => {
+            assert_eq!(event.emoji.name.as_deref(), Some("LUL"));
+            assert!(event.emoji.animated);
+            assert!(event.member.is_some());
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn message_reaction_remove() {
+    match dispatch(
+        "MESSAGE_REACTION_REMOVE",
+        json!({
+            "user_id": "80351110224678912",
+            "channel_id": "290926798999357250",
+            "message_id": "334385199974967042",
+            "emoji": { "id": null, "name": "🔥" }
+        }),
+    ) {
+        DispatchEventKind::MessageReactionRemove(event) => {
+            assert!(event.emoji.id.is_none());
+            assert_eq!(event.emoji.name.as_deref(), Some("🔥"));
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn message_reaction_remove_all() {
+    assert!(matches!(
+        dispatch(
+            "MESSAGE_REACTION_REMOVE_ALL",
+            json!({
+                "channel_id": "290926798999357250",
+                "message_id": "334385199974967042",
+                "guild_id": "290926798626357999"
+            }),
+        ),
+        DispatchEventKind::MessageReactionRemoveAll(_)
+    ));
+}
+
+#[test]
+fn message_reaction_remove_emoji() {
+    match dispatch(
+        "MESSAGE_REACTION_REMOVE_EMOJI",
+        json!({
+            "channel_id": "290926798999357250",
+            "guild_id": "290926798626357999",
+            "message_id": "334385199974967042",
+            "emoji": emoji()
+        }),
+    ) {
+        DispatchEventKind::MessageReactionRemoveEmoji(event) => {
+            assert_eq!(event.emoji.id, Some(EmojiId(41771983429993937)))
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn presence_update() {
+    match dispatch(
+        "PRESENCE_UPDATE",
+        json!({
+            "user": { "id": "80351110224678912" },
+            "guild_id": "290926798626357999",
+            "status": "dnd",
+            "activities": [{
+                "name": "Rocket League",
+                "type": 0,
+                "created_at": 1507665886091u64
+            }],
+            "client_status": { "desktop": "dnd", "mobile": "idle" }
+        }),
+    ) {
+        DispatchEventKind::PresenceUpdate(presence) => {
+            assert_eq!(presence.user.id, UserId(80351110224678912));
+            assert_eq!(presence.status, Status::DoNotDisturb);
+            assert_eq!(presence.activities[0].name, "Rocket League");
+            assert_eq!(presence.client_status.mobile, Some(Status::Idle));
+            assert_eq!(presence.client_status.web, None);
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn typing_start() {
+    match dispatch(
+        "TYPING_START",
+        json!({
+            "channel_id": "290926798999357250",
+            "guild_id": "290926798626357999",
+            "user_id": "80351110224678912",
+            "timestamp": 1640995200,
+            "member": member()
+        }),
+    ) {
+        DispatchEventKind::TypingStart(event) => {
+            assert_eq!(event.timestamp.to_rfc3339(), "2022-01-01T00:00:00+00:00")
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn user_update() {
+    match dispatch("USER_UPDATE", user()) {
+        DispatchEventKind::UserUpdate(user) => assert_eq!(user.discriminator, "1337"),
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn voice_state_update() {
+    match dispatch(
+        "VOICE_STATE_UPDATE",
+        json!({
+            "guild_id": "290926798626357999",
+            "channel_id": "157733188964188161",
+            "user_id": "80351110224678912",
+            "member": member(),
+            "session_id": "90326bd25d71d39b9ef95b299e3872ff",
+            "deaf": false,
+            "mute": false,
+            "self_deaf": false,
+            "self_mute": true,
+            "self_stream": false,
+            "self_video": false,
+            "suppress": false,
+            "request_to_speak_timestamp": "2021-03-31T18:45:31.297561+00:00"
+        }),
+    ) {
+        DispatchEventKind::VoiceStateUpdate(state) => {
+            assert_eq!(state.channel_id, Some(ChannelId(157733188964188161)));
+            assert!(state.request_to_speak_timestamp.is_some());
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn voice_server_update() {
+    match dispatch(
+        "VOICE_SERVER_UPDATE",
+        json!({
+            "token": "my_token",
+            "guild_id": "41771983423143937",
+            "endpoint": "smart.loyal.discord.gg"
+        }),
+    ) {
+        DispatchEventKind::VoiceServerUpdate(event) => {
+            assert_eq!(event.endpoint.as_deref(), Some("smart.loyal.discord.gg"))
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn webhooks_update() {
+    let event = dispatch(
+        "WEBHOOKS_UPDATE",
+        json!({ "guild_id": "41771983423143937", "channel_id": "290926798999357250" }),
+    );
+    assert_eq!(event.guild_id(), Some(GuildId(41771983423143937)));
+    assert!(matches!(event, DispatchEventKind::WebhooksUpdate(_)));
+}
+
+#[test]
+fn unknown() {
+    match dispatch("STAGE_INSTANCE_CREATE", json!({ "guild_id": "1" })) {
+        DispatchEventKind::Unknown(event) => {
+            assert_eq!(event.name, "STAGE_INSTANCE_CREATE");
+            assert_eq!(event.data["guild_id"], "1");
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn malformed() {
+    match dispatch(
+        "MESSAGE_UPDATE",
+        json!({ "id": "not a snowflake", "channel_id": "290926798999357250" }),
+    ) {
+        DispatchEventKind::Unknown(event) => {
+            assert_eq!(event.name, "MESSAGE_UPDATE");
+            assert_eq!(event.data["id"], "not a snowflake");
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
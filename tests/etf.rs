@@ -0,0 +1,189 @@
+use std::net::TcpListener;
+use std::thread;
+use std::time::Duration;
+
+use harmony::gateway::{etf, DispatchEventKind, Encoding, Event, Gateway, Intents, Status};
+use harmony::model::id::{RoleId, UserId};
+use harmony::model::Activity;
+use harmony::Config;
+use serde_json::{json, Value};
+
+const HELLO: &[u8] = include_bytes!("fixtures/etf/hello.etf");
+const HEARTBEAT_ACK: &[u8] = include_bytes!("fixtures/etf/heartbeat_ack.etf");
+const INVALID_SESSION: &[u8] = include_bytes!("fixtures/etf/invalid_session.etf");
+const READY: &[u8] = include_bytes!("fixtures/etf/ready.etf");
+const READY_COMPRESSED: &[u8] = include_bytes!("fixtures/etf/ready_compressed.etf");
+const MESSAGE_CREATE: &[u8] = include_bytes!("fixtures/etf/message_create.etf");
+
+const FIXTURES: &[&[u8]] = &[
+    HELLO,
+    HEARTBEAT_ACK,
+    INVALID_SESSION,
+    READY,
+    READY_COMPRESSED,
+    MESSAGE_CREATE,
+];
+
+fn event(data: &[u8]) -> Event {
+    serde_json::from_value(etf::decode(data).unwrap()).unwrap()
+}
+
+#[test]
+fn hello() {
+    match event(HELLO) {
+        Event::Hello(hello) => assert_eq!(hello.heartbeat_interval, Duration::from_millis(41250)),
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn heartbeat_ack() {
+    assert!(matches!(event(HEARTBEAT_ACK), Event::HeartbeatAck));
+}
+
+#[test]
+fn invalid_session() {
+    assert!(matches!(
+        event(INVALID_SESSION),
+        Event::InvalidSession(false)
+    ));
+}
+
+#[test]
+fn ready() {
+    let dispatch = event(READY).into_dispatch().unwrap();
+    assert_eq!(dispatch.sequence_number, 1);
+    let ready = dispatch.kind.into_ready().unwrap();
+    assert_eq!(ready.version, 9);
+    assert_eq!(ready.session_id, "d2e8ee2a8d9c6a8b8f5c0f0d");
+    assert_eq!(ready.shard, Some([0, 1]));
+    assert_eq!(ready.user.id, UserId(80351110224678912));
+    assert!(ready.user.bot);
+}
+
+#[test]
+fn compressed() {
+    assert_eq!(
+        etf::decode(READY_COMPRESSED).unwrap(),
+        etf::decode(READY).unwrap()
+    );
+}
+
+#[test]
+fn message_create() {
+    let dispatch = event(MESSAGE_CREATE).into_dispatch().unwrap();
+    assert_eq!(dispatch.sequence_number, 2);
+    let message = match dispatch.kind {
+        DispatchEventKind::MessageCreate(message) => message,
+        kind => panic!("unexpected event {:?}", kind),
+    };
+    assert_eq!(message.content, "Supa Hot");
+    assert_eq!(message.author.username, "Nelly");
+    assert_eq!(message.mention_roles, vec![RoleId(41771983423143936)]);
+    assert_eq!(message.nonce, json!(-1234567890));
+    assert!(message.edited_timestamp.is_none());
+}
+
+#[test]
+fn round_trip() {
+    for fixture in FIXTURES {
+        let value = etf::decode(fixture).unwrap();
+        assert_eq!(etf::decode(&etf::encode(&value)).unwrap(), value);
+    }
+}
+
+#[test]
+fn round_trip_values() {
+    let value = json!({
+        "null": null,
+        "bool": true,
+        "small": 255,
+        "negative": -1,
+        "integer": 2147483647,
+        "big": 18446744073709551615u64,
+        "negative_big": -9223372036854775808i64,
+        "float": 1.5,
+        "string": "Supa Hot",
+        "empty": [],
+        "list": [1, "two", [3]],
+        "map": {},
+    });
+    assert_eq!(etf::decode(&etf::encode(&value)).unwrap(), value);
+}
+
+#[test]
+fn invalid() {
+    assert!(etf::decode(&[]).is_err());
+    assert!(etf::decode(&[130, 97, 1]).is_err());
+    assert!(etf::decode(&HELLO[..HELLO.len() - 1]).is_err());
+    assert!(etf::decode(&[131, 97, 1, 97]).is_err());
+    // Compressed, claiming 4 GiB uncompressed.
+    assert!(etf::decode(&[131, 80, 255, 255, 255, 255, 0]).is_err());
+}
+
+#[test]
+fn nested_too_deeply() {
+    let mut data = vec![131];
+    for _ in 0..100_000 {
+        data.extend_from_slice(&[104, 1]);
+    }
+    data.push(106);
+    assert!(etf::decode(&data).is_err());
+    let mut data = vec![131];
+    for _ in 0..100 {
+        data.extend_from_slice(&[104, 1]);
+    }
+    data.push(106);
+    assert!(etf::decode(&data).is_ok());
+}
+
+#[test]
+fn gateway_commands() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut socket = tungstenite::accept(stream).unwrap();
+        socket
+            .write_message(tungstenite::Message::Binary(HELLO.to_vec()))
+            .unwrap();
+        (0..4)
+            .map(|_| match socket.read_message().unwrap() {
+                tungstenite::Message::Binary(data) => etf::decode(&data).unwrap(),
+                message => panic!("unexpected message {:?}", message),
+            })
+            .collect::<Vec<_>>()
+    });
+    let config = Config::new()
+        .gateway_url(format!("ws://127.0.0.1:{}", port))
+        .tls(false)
+        .encoding(Encoding::Etf);
+    let mut gateway =
+        Gateway::connect_with_config(config, "Bot token", Intents::GUILD_MESSAGES).unwrap();
+    let events = gateway.wait_events(None).unwrap();
+    assert!(matches!(events[..], [Event::Hello(_)]));
+    gateway.identify("Bot token").unwrap();
+    gateway.heartbeat(Some(42)).unwrap();
+    gateway
+        .resume("Bot token", &Some("session".to_string()), Some(42))
+        .unwrap();
+    gateway
+        .sender()
+        .presence_update(Status::Idle, Some(Activity::playing("chess")))
+        .unwrap();
+    assert!(gateway.get_events().unwrap().is_empty());
+    let payloads = server.join().unwrap();
+    assert_eq!(payloads[0]["op"], 2);
+    assert_eq!(payloads[0]["d"]["token"], "Bot token");
+    assert_eq!(payloads[0]["d"]["intents"], json!(Intents::GUILD_MESSAGES));
+    assert_eq!(payloads[0]["d"]["shard"], json!([0, 1]));
+    assert_eq!(payloads[1], json!({ "op": 1, "d": 42 }));
+    assert_eq!(
+        payloads[2],
+        json!({ "op": 6, "d": { "token": "Bot token", "session_id": "session", "seq": 42 } })
+    );
+    assert_eq!(payloads[3]["op"], 3);
+    assert_eq!(payloads[3]["d"]["status"], "idle");
+    assert_eq!(payloads[3]["d"]["activities"][0]["name"], "chess");
+    assert_eq!(payloads[3]["d"]["afk"], Value::Bool(false));
+}
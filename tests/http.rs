@@ -0,0 +1,269 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use harmony::http::{Http, Method, Request, RetryPolicy, Route};
+use harmony::model::id::{ChannelId, MessageId};
+use harmony::{Config, Error, JsonErrorCode};
+
+fn serve(responses: Vec<String>) -> Http {
+    serve_with(Config::new().retry_policy(RetryPolicy::none()), responses)
+}
+
+fn serve_with(config: Config, responses: Vec<String>) -> Http {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    thread::spawn(move || {
+        for response in responses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            let mut stream = reader.into_inner();
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+    let config = config.api_url(format!("http://127.0.0.1:{}", port));
+    Http::with_config(Arc::from("Bot token"), config)
+}
+
+fn response(status: &str, headers: &[(&str, &str)], body: &str) -> String {
+    let mut response = format!("HTTP/1.1 {}\r\n", status);
+    for (name, value) in headers {
+        response.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    response.push_str(&format!(
+        "Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    ));
+    response
+}
+
+// `X-RateLimit-Reset` for a reset `secs` from now.
+fn reset_in(secs: f64) -> String {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap();
+    format!("{:.3}", now.as_secs_f64() + secs)
+}
+
+// A second route in the same channel as `delete_message`.
+fn get_pins(http: &Http) -> harmony::Result {
+    let route = Route::new(Method::Get, "/channels/{channel_id}/pins", ChannelId(1));
+    http.request(Request::new(route, "/channels/1/pins"))?;
+    Ok(())
+}
+
+#[test]
+fn shared_bucket() {
+    let reset = reset_in(1.0);
+    let http = serve(vec![
+        response(
+            "204 No Content",
+            &[
+                ("X-RateLimit-Bucket", "abc"),
+                ("X-RateLimit-Limit", "2"),
+                ("X-RateLimit-Remaining", "1"),
+                ("X-RateLimit-Reset", &reset),
+            ],
+            "",
+        ),
+        response(
+            "204 No Content",
+            &[
+                ("X-RateLimit-Bucket", "abc"),
+                ("X-RateLimit-Limit", "2"),
+                ("X-RateLimit-Remaining", "0"),
+                ("X-RateLimit-Reset", &reset),
+            ],
+            "",
+        ),
+        response("204 No Content", &[], ""),
+    ]);
+    http.delete_message(ChannelId(1), MessageId(2)).unwrap();
+    get_pins(&http).unwrap();
+    // The pins used up the bucket that deleting messages shares with them.
+    let start = Instant::now();
+    http.delete_message(ChannelId(1), MessageId(3)).unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(500));
+}
+
+fn global_rate_limit(header: (&str, &str)) {
+    let http = serve(vec![
+        response(
+            "429 Too Many Requests",
+            &[header],
+            r#"{"message": "You are being rate limited.", "retry_after": 1.0, "global": false}"#,
+        ),
+        response("204 No Content", &[], ""),
+        response("204 No Content", &[], ""),
+    ]);
+    let other = http.clone();
+    let first = thread::spawn(move || http.delete_message(ChannelId(1), MessageId(2)));
+    thread::sleep(Duration::from_millis(200));
+    let start = Instant::now();
+    get_pins(&other).unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(500));
+    first.join().unwrap().unwrap();
+}
+
+#[test]
+fn global_rate_limit_header() {
+    global_rate_limit(("X-RateLimit-Global", "true"));
+}
+
+#[test]
+fn global_rate_limit_scope() {
+    global_rate_limit(("X-RateLimit-Scope", "global"));
+}
+
+#[test]
+fn global_request_cap() {
+    let http = serve(vec![response("204 No Content", &[], ""); 51]);
+    let start = Instant::now();
+    for message_id in 0..51 {
+        http.delete_message(ChannelId(1), MessageId(message_id))
+            .unwrap();
+    }
+    assert!(start.elapsed() >= Duration::from_millis(900));
+}
+
+#[test]
+fn garbage_rate_limit_header() {
+    let http = serve(vec![response(
+        "204 No Content",
+        &[("X-RateLimit-Remaining", "lots")],
+        "",
+    )]);
+    // The message is deleted, so the call must not fail and invite a retry.
+    assert!(http.delete_message(ChannelId(1), MessageId(2)).is_ok());
+}
+
+#[test]
+fn negative_rate_limit_reset() {
+    let http = serve(vec![response(
+        "204 No Content",
+        &[("X-RateLimit-Reset", "-12.5")],
+        "",
+    )]);
+    assert!(http.delete_message(ChannelId(1), MessageId(2)).is_ok());
+}
+
+#[test]
+fn garbage_too_many_requests_body() {
+    let http = serve(vec![response("429 Too Many Requests", &[], "<html>")]);
+    let result = http.delete_message(ChannelId(1), MessageId(2));
+    assert!(matches!(result, Err(Error::Io(_))));
+}
+
+#[test]
+fn invalid_retry_after() {
+    let http = serve(vec![response(
+        "429 Too Many Requests",
+        &[],
+        r#"{"message": "You are being rate limited.", "retry_after": -1, "global": false}"#,
+    )]);
+    let result = http.delete_message(ChannelId(1), MessageId(2));
+    assert!(matches!(
+        result,
+        Err(Error::InvalidRateLimit {
+            name: "retry_after",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn garbage_success_body() {
+    let http = serve(vec![response("200 OK", &[], "{\"id\": ")]);
+    let result = http.create_message(ChannelId(1), |m| m.content("hello"));
+    assert!(matches!(result, Err(Error::Io(_))));
+}
+
+#[test]
+fn non_json_error_body() {
+    let http = serve(vec![response(
+        "502 Bad Gateway",
+        &[],
+        "<html>bad gateway</html>",
+    )]);
+    let result = http.delete_message(ChannelId(1), MessageId(2));
+    assert!(matches!(
+        result,
+        Err(Error::Api {
+            status: 502,
+            code: JsonErrorCode::General,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn json_error_body() {
+    let http = serve(vec![
+        response(
+            "404 Not Found",
+            &[],
+            r#"{"message": "Unknown Message", "code": 10008}"#,
+        ),
+        response(
+            "404 Not Found",
+            &[],
+            r#"{"message": "Unknown Channel", "code": 10003}"#,
+        ),
+    ]);
+    let result = http.delete_message(ChannelId(1), MessageId(2));
+    assert!(matches!(
+        result,
+        Err(Error::Api {
+            status: 404,
+            code: JsonErrorCode::UnknownMessage,
+            ..
+        })
+    ));
+    assert!(matches!(http.get_channel(ChannelId(1)), Ok(None)));
+}
+
+#[test]
+fn retry_server_error() {
+    let config = Config::new().retry_policy(RetryPolicy::new().base_delay(Duration::ZERO));
+    let http = serve_with(
+        config,
+        vec![
+            response("502 Bad Gateway", &[], "<html>bad gateway</html>"),
+            response("503 Service Unavailable", &[], ""),
+            response("204 No Content", &[], ""),
+        ],
+    );
+    assert!(http.delete_message(ChannelId(1), MessageId(2)).is_ok());
+}
+
+#[test]
+fn no_retry_non_idempotent() {
+    let config = Config::new().retry_policy(RetryPolicy::new().base_delay(Duration::ZERO));
+    let http = serve_with(
+        config,
+        vec![
+            response("502 Bad Gateway", &[], "<html>bad gateway</html>"),
+            response("200 OK", &[], "{}"),
+        ],
+    );
+    let result = http.create_message(ChannelId(1), |m| m.content("hello"));
+    assert!(matches!(result, Err(Error::Api { status: 502, .. })));
+}